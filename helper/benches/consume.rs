@@ -0,0 +1,189 @@
+// Compares the buffer rewrite of `Lines` with the split-based implementation it replaced,
+// both parsing with `FromStr`, then the byte-level integer scanner with `FromStr` on the
+// rewritten buffer. Most of the speedup is the buffer rewrite.
+//
+// ```shell
+// cargo bench -p helper
+// ```
+use std::{
+    hint::black_box,
+    num::ParseIntError,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use helper::Lines;
+
+// the `Lines` implementation before the byte-level scanner, kept as it was
+#[allow(clippy::wrong_self_convention)]
+mod legacy {
+    use std::{
+        collections::VecDeque,
+        str::{FromStr, Split},
+    };
+
+    pub struct Line<'a> {
+        value: Split<'a, &'a str>,
+    }
+    impl<'a> Line<'a> {
+        pub fn new(value: &'a str) -> Self {
+            Line {
+                value: value.split(" "),
+            }
+        }
+        pub fn next_data(&mut self) -> Option<&'a str> {
+            self.value.next()
+        }
+        pub fn to_vec<T: FromStr>(self) -> Vec<T> {
+            self.value.filter_map(|s| s.parse::<T>().ok()).collect()
+        }
+    }
+
+    pub struct Lines<'a> {
+        inner: VecDeque<Line<'a>>,
+    }
+    impl<'a> Lines<'a> {
+        pub fn new(s: &'a str) -> Self {
+            let inner = s.split("\n").map(Line::new).collect();
+            Lines { inner }
+        }
+        pub fn next_line(&mut self) -> Option<Line<'a>> {
+            self.inner.pop_front()
+        }
+        pub fn next_data(&mut self) -> Option<&str> {
+            self.inner.front_mut()?.next_data().or_else(|| {
+                self.next_line();
+                self.next_data()
+            })
+        }
+        pub fn consume<T: FromStr>(&mut self) -> Option<T> {
+            self.next_data().and_then(|s| s.parse().ok())
+        }
+        pub fn consume_to_vec<T: FromStr>(&mut self) -> Option<Vec<T>> {
+            while let Some(line) = self.next_line() {
+                let v = line.to_vec();
+                if !v.is_empty() {
+                    return Some(v);
+                }
+            }
+            None
+        }
+    }
+}
+
+// an `i64` that `Lines` can only read with `FromStr`, to tell the buffer rewrite from the
+// byte-level scanner
+struct ViaFromStr(i64);
+impl FromStr for ViaFromStr {
+    type Err = ParseIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(ViaFromStr)
+    }
+}
+
+const N: usize = 1_000_000;
+const ITERATIONS: usize = 10;
+
+fn main() {
+    let values = (0..N as i64)
+        .map(|i| (i * 7_919 % 2_000_000_007) - 1_000_000_000)
+        .collect::<Vec<_>>();
+    let expected = values.iter().sum::<i64>();
+    let one_line = values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let one_line = format!("{}\n{}\n", N, one_line);
+    let one_per_line = values
+        .iter()
+        .map(|v| format!("{}\n", v))
+        .collect::<String>();
+    let one_per_line = format!("{}\n{}", N, one_per_line);
+
+    println!("{} integers, best of {} runs", N, ITERATIONS);
+    println!("buffer rewrite, both with `FromStr`:");
+    compare("consume, one line", &one_line, expected);
+    compare("consume, one per line", &one_per_line, expected);
+
+    let split = bench(|| {
+        let mut lines = legacy::Lines::new(&one_line);
+        let _ = lines.consume::<usize>();
+        lines.consume_to_vec::<i64>().unwrap().iter().sum::<i64>()
+    });
+    let buffer = bench(|| {
+        let mut lines = Lines::new(&one_line);
+        let _ = lines.consume::<usize>();
+        let values = lines.consume_to_vec::<ViaFromStr>().unwrap();
+        values.iter().map(|v| v.0).sum::<i64>()
+    });
+    report("consume_to_vec, one line", split, buffer);
+
+    // the same buffer with `FromStr` in place of the byte-level scanner
+    println!("byte-level scanner, both on the rewritten buffer:");
+    for (name, input) in [
+        ("consume, one line", &one_line),
+        ("consume, one per line", &one_per_line),
+    ] {
+        let from_str = bench(|| {
+            let mut lines = Lines::new(input);
+            let n = lines.consume::<usize>().unwrap();
+            (0..n)
+                .map(|_| lines.consume::<ViaFromStr>().unwrap().0)
+                .sum::<i64>()
+        });
+        let scanner = bench(|| {
+            let mut lines = Lines::new(input);
+            let n = lines.consume::<usize>().unwrap();
+            (0..n).map(|_| lines.consume::<i64>().unwrap()).sum::<i64>()
+        });
+        report_with(name, ("FromStr", from_str), ("scanner", scanner));
+    }
+}
+
+fn compare(name: &str, input: &str, expected: i64) {
+    let split = bench(|| {
+        let mut lines = legacy::Lines::new(input);
+        let n = lines.consume::<usize>().unwrap();
+        let sum = (0..n).map(|_| lines.consume::<i64>().unwrap()).sum::<i64>();
+        assert_eq!(sum, expected);
+        sum
+    });
+    let buffer = bench(|| {
+        let mut lines = Lines::new(input);
+        let n = lines.consume::<usize>().unwrap();
+        let sum = (0..n)
+            .map(|_| lines.consume::<ViaFromStr>().unwrap().0)
+            .sum::<i64>();
+        assert_eq!(sum, expected);
+        sum
+    });
+    report(name, split, buffer);
+}
+
+fn bench<F: FnMut() -> i64>(mut f: F) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, split: Duration, buffer: Duration) {
+    report_with(name, ("split", split), ("buffer", buffer));
+}
+
+fn report_with(name: &str, (a_name, a): (&str, Duration), (b_name, b): (&str, Duration)) {
+    println!(
+        "  {:<26} {} {:>8.2?}  {} {:>8.2?}  ({:.1}x)",
+        name,
+        a_name,
+        a,
+        b_name,
+        b,
+        a.as_secs_f64() / b.as_secs_f64()
+    );
+}
@@ -0,0 +1,32 @@
+use crate::{Lines, ParseError, Readable};
+
+/// A row of tokens that [`Lines::consume_columns`] splits into one `Vec` per column.
+///
+/// Implemented for tuples of up to six [`Readable`] types, e.g. `(usize, i64)` gives
+/// `(Vec<usize>, Vec<i64>)` and `(Usize1, i64)` gives the same with 0-based indices.
+pub trait Columns {
+    type Vecs;
+    fn read_columns(lines: &mut Lines<'_>, rows: usize) -> Result<Self::Vecs, ParseError>;
+}
+
+macro_rules! impl_columns {
+    ($($t:ident),*) => {
+        impl<$($t: Readable),*> Columns for ($($t,)*) {
+            type Vecs = ($(Vec<$t::Output>,)*);
+            #[allow(non_snake_case)]
+            fn read_columns(lines: &mut Lines<'_>, rows: usize) -> Result<Self::Vecs, ParseError> {
+                $(let mut $t = Vec::with_capacity(rows);)*
+                for _ in 0..rows {
+                    $($t.push($t::read(lines)?);)*
+                }
+                Ok(($($t,)*))
+            }
+        }
+    };
+}
+impl_columns!(A);
+impl_columns!(A, B);
+impl_columns!(A, B, C);
+impl_columns!(A, B, C, D);
+impl_columns!(A, B, C, D, E);
+impl_columns!(A, B, C, D, E, F);
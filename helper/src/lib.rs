@@ -1,54 +1,450 @@
 use std::{
-    collections::VecDeque,
-    str::{FromStr, Split},
+    any::{type_name, Any},
+    fmt::{self, Debug, Display},
+    io::BufRead,
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
 };
 
+mod checked;
+mod columns;
+mod readable;
+mod token;
+pub use checked::CheckedInt;
+pub use columns::Columns;
+pub use readable::{ReadFromStr, ReadReadable, Readable, Reader};
+use token::parse_token;
+
+/// Marks a token read as its characters, e.g. a grid row like `#..#`.
+///
+/// It holds no value, like [`Usize1`]: [`Readable`] reads it as a `Vec<char>`, and `#[pte]`
+/// reads `Vec<Chars>` one token per element and passes it on as `Vec<Vec<char>>`.
+pub enum Chars {}
+/// Marks a token read as its bytes, the `Vec<u8>` counterpart of [`Chars`].
+pub enum Bytes {}
+/// Marks a 1-based index in the input, which is read as a 0-based `usize`.
+///
+/// It holds no value: [`Readable`] reads it as its `usize` output, like
+/// `lines.read::<(Usize1, i64)>()` gives `(usize, i64)`, and `#[pte]` passes the argument on
+/// as `usize`. By hand, read it with [`Lines::try_consume_usize1`].
+pub enum Usize1 {}
+/// The `isize` counterpart of [`Usize1`], read with [`Lines::try_consume_isize1`].
+pub enum Isize1 {}
+
+/// Describes where and why the input could not be read as the expected type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    // None means the input ended before the expected token
+    token: Option<String>,
+    expected: &'static str,
+    // the I/O error that ended the input, as a message so that the error stays `Clone` and `Eq`
+    io_error: Option<String>,
+}
+impl ParseError {
+    fn invalid_token<T>(line: usize, column: usize, token: &[u8]) -> Self {
+        ParseError {
+            line,
+            column,
+            token: Some(String::from_utf8_lossy(token).into_owned()),
+            expected: type_name::<T>(),
+            io_error: None,
+        }
+    }
+    fn end_of_input<T>(line: usize, column: usize) -> Self {
+        ParseError {
+            line,
+            column,
+            token: None,
+            expected: type_name::<T>(),
+            io_error: None,
+        }
+    }
+    /// 1-based line number of the offending token
+    pub fn line(&self) -> usize {
+        self.line
+    }
+    /// 1-based column of the offending token
+    pub fn column(&self) -> usize {
+        self.column
+    }
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+    pub fn is_end_of_input(&self) -> bool {
+        self.token.is_none() && self.io_error.is_none()
+    }
+    /// The error that stopped the input from being read, such as invalid UTF-8.
+    pub fn io_error(&self) -> Option<&str> {
+        self.io_error.as_deref()
+    }
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected `{}`, ",
+            self.line, self.column, self.expected
+        )?;
+        match (&self.token, &self.io_error) {
+            (Some(token), _) => write!(f, "found `{}`", token),
+            (None, Some(io_error)) => write!(f, "failed to read the input: {}", io_error),
+            (None, None) => write!(f, "found end of input"),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
 #[derive(Debug)]
 pub struct Line<'a> {
-    value: Split<'a, &'a str>,
+    value: &'a str,
+    pos: usize,
+    number: usize,
+    column: usize,
 }
 impl<'a> Line<'a> {
     pub fn new(value: &'a str) -> Self {
+        Self::with_position(value, 1, 1)
+    }
+    fn with_position(value: &'a str, number: usize, column: usize) -> Self {
         Line {
-            value: value.split(" "),
+            value,
+            pos: 0,
+            number,
+            column,
         }
     }
     pub fn next_data(&mut self) -> Option<&'a str> {
-        self.value.next()
+        self.next_token().map(|(_, data)| data)
+    }
+    pub fn to_vec<T: FromStr + 'static>(mut self) -> Vec<T> {
+        let mut result = Vec::new();
+        while let Some(data) = self.next_data() {
+            if let Some(v) = parse_token(data) {
+                result.push(v);
+            }
+        }
+        result
     }
-    pub fn to_vec<T: FromStr>(self) -> Vec<T> {
-        self.value
-            .into_iter()
-            .filter_map(|s| s.parse::<T>().ok())
-            .collect()
+    pub fn try_to_vec<T: FromStr + 'static>(mut self) -> Result<Vec<T>, ParseError> {
+        let mut result = Vec::new();
+        while let Some((column, data)) = self.next_token() {
+            result.push(parse(data, self.number, column)?);
+        }
+        Ok(result)
+    }
+    fn next_token(&mut self) -> Option<(usize, &'a str)> {
+        let bytes = self.value.as_bytes();
+        while self.pos < bytes.len() && is_separator(bytes[self.pos]) {
+            self.pos += 1;
+        }
+        if self.pos == bytes.len() {
+            return None;
+        }
+        let start = self.pos;
+        while self.pos < bytes.len() && !is_separator(bytes[self.pos]) {
+            self.pos += 1;
+        }
+        Some((self.column + start, &self.value[start..self.pos]))
     }
 }
 
-#[derive(Debug)]
+/// A saved cursor position of [`Lines`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pos: usize,
+    line: usize,
+    line_start: usize,
+}
+
+/// The limit of [`Lines`] that was in effect before [`Lines::enter_lines`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineLimit(Option<usize>);
+
+/// The input, read through a cursor.
+///
+/// The cursor is either at the start of a line or in a started line, one that some tokens of
+/// were consumed from. Token reads like [`Lines::try_consume`] skip line breaks and empty lines,
+/// while line reads say which line they take:
+///
+/// - [`Lines::try_consume_rest_of_line`]: what is left of the started line, maybe nothing,
+///   or else the line at the cursor
+/// - [`Lines::try_consume_next_line`]: the line after the started line, or else the line at
+///   the cursor
+/// - [`Lines::try_consume_to_vec`]: the rest of the started line if tokens are left on it,
+///   or else the next non-empty line
+/// - [`Lines::try_consume_rows`]: `n` lines taken like [`Lines::try_consume_to_vec`]
+/// - [`Lines::try_consume_to_two_d_vec`]: every line up to the line limit or the end of input
 pub struct Lines<'a> {
-    inner: VecDeque<Line<'a>>,
+    buf: String,
+    pos: usize,
+    // 0-based index of the line the cursor is on and the offset where it starts
+    line: usize,
+    line_start: usize,
+    reader: Option<Box<dyn BufRead + 'a>>,
+    read_lines: usize,
+    // set when the reader fails, which ends the input there
+    io_error: Option<String>,
+    // index of the first line that cannot be read
+    line_limit: Option<usize>,
 }
 impl<'a> Lines<'a> {
-    pub fn new(s: &'a str) -> Self {
-        let inner = s.split("\n").map(|s| Line::new(s)).collect();
-        Lines { inner }
+    pub fn new(s: &str) -> Self {
+        Lines {
+            buf: s.to_string(),
+            pos: 0,
+            line: 0,
+            line_start: 0,
+            reader: None,
+            read_lines: 0,
+            io_error: None,
+            line_limit: None,
+        }
+    }
+    /// Reads the input lazily from `reader`, one line at a time as tokens are consumed.
+    pub fn from_reader<R: BufRead + 'a>(reader: R) -> Self {
+        Lines {
+            reader: Some(Box::new(reader)),
+            ..Self::new("")
+        }
+    }
+    /// Limits the input to the next `n` lines.
+    ///
+    /// When the cursor is in the middle of a line, the rest of that line is still read.
+    pub fn limit_lines(&mut self, n: usize) {
+        self.line_limit = Some(self.next_line_index() + n);
+    }
+    /// Calls `read` with the input limited to the next `n` lines, like [`Lines::limit_lines`],
+    /// then skips what `read` left of them and lifts the limit again.
+    pub fn within_lines<T>(&mut self, n: usize, read: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.enter_lines(n);
+        let value = read(self);
+        self.leave_lines(outer);
+        value
+    }
+    /// Limits the input to the next `n` lines within the current limit,
+    /// until [`Lines::leave_lines`] is called with the returned limit.
+    pub fn enter_lines(&mut self, n: usize) -> LineLimit {
+        let outer = self.line_limit;
+        self.limit_lines(n);
+        self.line_limit = self
+            .line_limit
+            .map(|limit| outer.map_or(limit, |outer| limit.min(outer)));
+        LineLimit(outer)
+    }
+    /// Skips the rest of the lines from [`Lines::enter_lines`] and restores the outer limit.
+    pub fn leave_lines(&mut self, outer: LineLimit) {
+        while self.next_line().is_some() {}
+        self.line_limit = outer.0;
+    }
+    /// Like [`Lines::leave_lines`], but a token left in the lines or an input that ends before
+    /// them is an error instead of being ignored.
+    pub fn finish_lines(&mut self, outer: LineLimit) -> Result<(), ParseError> {
+        if let Some((start, end)) = self.next_token() {
+            return Err(ParseError {
+                expected: "no more tokens",
+                ..ParseError::invalid_token::<()>(
+                    self.line + 1,
+                    start - self.line_start + 1,
+                    &self.buf.as_bytes()[start..end],
+                )
+            });
+        }
+        if self
+            .line_limit
+            .is_some_and(|limit| self.next_line_index() < limit)
+        {
+            return Err(ParseError {
+                expected: "another line",
+                ..self.end_of_input::<()>()
+            });
+        }
+        self.line_limit = outer.0;
+        Ok(())
+    }
+    /// Checks that the whole input is read: a token left, even after the line limit, or an input
+    /// that ends before the limit is an error.
+    pub fn finish(&mut self) -> Result<(), ParseError> {
+        self.finish_lines(LineLimit(None))?;
+        // the limit is lifted by now, so this checks the lines after it
+        self.finish_lines(LineLimit(None))
+    }
+    pub fn next_line(&mut self) -> Option<Line<'_>> {
+        let (start, end) = self.line_range()?;
+        let (number, column) = (self.line + 1, start - self.line_start + 1);
+        if end < self.buf.len() {
+            self.pos = end + 1;
+            self.line += 1;
+            self.line_start = self.pos;
+        } else {
+            self.pos = end;
+        }
+        Some(Line::with_position(&self.buf[start..end], number, column))
     }
-    pub fn next_line(&mut self) -> Option<Line<'a>> {
-        self.inner.pop_front()
+    /// Returns what `next_line` would return, without moving the cursor.
+    pub fn peek_line(&mut self) -> Option<Line<'_>> {
+        let (start, end) = self.line_range()?;
+        Some(Line::with_position(
+            &self.buf[start..end],
+            self.line + 1,
+            start - self.line_start + 1,
+        ))
     }
     pub fn next_data(&mut self) -> Option<&str> {
-        self.inner.front_mut()?.next_data().or_else(|| {
-            self.next_line();
-            self.next_data()
+        let (start, end) = self.next_token()?;
+        Some(&self.buf[start..end])
+    }
+    /// Returns whether a token is left in the input, without moving the cursor.
+    pub fn has_next_data(&mut self) -> bool {
+        let checkpoint = self.checkpoint();
+        let found = self.skip_separators();
+        self.restore(checkpoint);
+        found
+    }
+    /// Saves the cursor so that [`Lines::restore`] can rewind to it.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            line: self.line,
+            line_start: self.line_start,
+        }
+    }
+    /// Moves the cursor back (or forward) to a saved checkpoint.
+    ///
+    /// Input read from the reader is kept, so nothing is read twice.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.pos = checkpoint.pos;
+        self.line = checkpoint.line;
+        self.line_start = checkpoint.line_start;
+    }
+    /// Parses the next token without consuming it.
+    pub fn peek<T: FromStr + 'static>(&mut self) -> Option<T> {
+        self.try_peek().ok()
+    }
+    pub fn try_peek<T: FromStr + 'static>(&mut self) -> Result<T, ParseError> {
+        let checkpoint = self.checkpoint();
+        let result = self.try_consume();
+        self.restore(checkpoint);
+        result
+    }
+    pub fn consume<T: FromStr + 'static>(&mut self) -> Option<T> {
+        self.try_consume().ok()
+    }
+    /// Integer types are parsed straight from the bytes of the token, other types with `FromStr`.
+    pub fn try_consume<T: FromStr + 'static>(&mut self) -> Result<T, ParseError> {
+        self.try_consume_with(parse_token)
+    }
+    // the next token converted by `convert`, which returns None for an invalid token
+    pub(crate) fn try_consume_with<T>(
+        &mut self,
+        convert: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<T, ParseError> {
+        let Some((start, end)) = self.next_token() else {
+            return Err(self.end_of_input::<T>());
+        };
+        let token = &self.buf[start..end];
+        convert(token).ok_or_else(|| {
+            ParseError::invalid_token::<T>(
+                self.line + 1,
+                start - self.line_start + 1,
+                token.as_bytes(),
+            )
         })
     }
-    pub fn consume<T: FromStr>(&mut self) -> Option<T> {
-        self.next_data().and_then(|s| s.parse().ok())
+    /// Reads the next `n` tokens, whether they are on one line or spread over several lines.
+    pub fn consume_n<T: FromStr + 'static>(&mut self, n: usize) -> Option<Vec<T>> {
+        self.try_consume_n(n).ok()
     }
-    pub fn consume_to_vec<T: FromStr>(&mut self) -> Option<Vec<T>> {
-        if self.is_empty() {
-            return None;
+    pub fn try_consume_n<T: FromStr + 'static>(&mut self, n: usize) -> Result<Vec<T>, ParseError> {
+        (0..n).map(|_| self.try_consume()).collect()
+    }
+    /// Reads a 1-based index and returns it 0-based; `0` is an error.
+    pub fn try_consume_usize1(&mut self) -> Result<usize, ParseError> {
+        self.try_consume_index("Usize1", |v: usize| v.checked_sub(1))
+    }
+    /// Reads a 1-based signed index and returns it 0-based.
+    pub fn try_consume_isize1(&mut self) -> Result<isize, ParseError> {
+        self.try_consume_index("Isize1", |v: isize| v.checked_sub(1))
+    }
+    /// Reads a token that must be one of `tags` and returns its index, as the tag of an enum `T`.
+    pub fn consume_tag<T>(&mut self, tags: &[&str]) -> Option<usize> {
+        self.try_consume_tag::<T>(tags).ok()
+    }
+    pub fn try_consume_tag<T>(&mut self, tags: &[&str]) -> Result<usize, ParseError> {
+        let Some((start, end)) = self.next_token() else {
+            return Err(self.end_of_input::<T>());
+        };
+        let token = &self.buf.as_bytes()[start..end];
+        tags.iter()
+            .position(|tag| tag.as_bytes() == token)
+            .ok_or_else(|| {
+                ParseError::invalid_token::<T>(self.line + 1, start - self.line_start + 1, token)
+            })
+    }
+    /// Reads a value of any [`Readable`] type, such as a tuple or a derived struct.
+    pub fn read<T: Readable>(&mut self) -> Result<T::Output, ParseError> {
+        T::read(self)
+    }
+    /// Reads `rows` rows like `a_i b_i` and returns one `Vec` per column.
+    pub fn consume_columns<T: Columns>(&mut self, rows: usize) -> Option<T::Vecs> {
+        self.try_consume_columns::<T>(rows).ok()
+    }
+    pub fn try_consume_columns<T: Columns>(&mut self, rows: usize) -> Result<T::Vecs, ParseError> {
+        T::read_columns(self, rows)
+    }
+    /// Reads the tokens left on the started line, or the whole line at the cursor,
+    /// and moves the cursor to the start of the next line.
+    pub fn consume_rest_of_line<T: FromStr + 'static>(&mut self) -> Option<Vec<T>> {
+        self.try_consume_rest_of_line().ok()
+    }
+    pub fn try_consume_rest_of_line<T: FromStr + 'static>(&mut self) -> Result<Vec<T>, ParseError> {
+        match self.next_line() {
+            Some(line) => line.try_to_vec(),
+            None => Err(self.end_of_input::<T>()),
+        }
+    }
+    /// Reads a whole line: the line after the started one, whose rest is skipped,
+    /// or the line at the cursor.
+    pub fn consume_next_line<T: FromStr + 'static>(&mut self) -> Option<Vec<T>> {
+        self.try_consume_next_line().ok()
+    }
+    pub fn try_consume_next_line<T: FromStr + 'static>(&mut self) -> Result<Vec<T>, ParseError> {
+        if self.pos > self.line_start {
+            self.next_line();
         }
+        self.try_consume_rest_of_line()
+    }
+    /// Like [`Lines::try_consume_rest_of_line`], reading each element of the line with `read`.
+    pub fn try_consume_rest_of_line_with<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        if self.peek_line().is_none() {
+            return Err(self.end_of_input::<T>());
+        }
+        let mut values = Vec::new();
+        while self.has_next_in_line() {
+            values.push(read(self)?);
+        }
+        self.next_line();
+        Ok(values)
+    }
+    /// Like [`Lines::try_consume_next_line`], reading each element of the line with `read`.
+    pub fn try_consume_next_line_with<T>(
+        &mut self,
+        read: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        if self.pos > self.line_start {
+            self.next_line();
+        }
+        self.try_consume_rest_of_line_with(read)
+    }
+    /// Reads the rest of the started line if tokens are left on it, or else the next non-empty line.
+    pub fn consume_to_vec<T: FromStr + 'static>(&mut self) -> Option<Vec<T>> {
         while let Some(line) = self.next_line() {
             let v = line.to_vec();
             // empty line is skipped
@@ -58,7 +454,28 @@ impl<'a> Lines<'a> {
         }
         None
     }
-    pub fn consume_to_two_d_vec<T: FromStr>(&mut self) -> Option<Vec<Vec<T>>> {
+    pub fn try_consume_to_vec<T: FromStr + 'static>(&mut self) -> Result<Vec<T>, ParseError> {
+        while let Some(line) = self.next_line() {
+            let v = line.try_to_vec()?;
+            // empty line is skipped
+            if !v.is_empty() {
+                return Ok(v);
+            }
+        }
+        Err(self.end_of_input::<T>())
+    }
+    /// Reads `n` rows, each like [`Lines::try_consume_to_vec`].
+    pub fn consume_rows<T: FromStr + 'static>(&mut self, n: usize) -> Option<Vec<Vec<T>>> {
+        self.try_consume_rows(n).ok()
+    }
+    pub fn try_consume_rows<T: FromStr + 'static>(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<Vec<T>>, ParseError> {
+        (0..n).map(|_| self.try_consume_to_vec()).collect()
+    }
+    /// Reads every non-empty line up to the line limit or the end of input, one row each.
+    pub fn consume_to_two_d_vec<T: FromStr + 'static>(&mut self) -> Option<Vec<Vec<T>>> {
         if self.is_empty() {
             return None;
         }
@@ -72,13 +489,268 @@ impl<'a> Lines<'a> {
         }
         Some(result)
     }
-    pub fn extend(&mut self, s: &'a str) {
-        let inner = s.split("\n").map(|s| Line::new(s)).collect::<Vec<_>>();
-        self.inner.extend(inner);
+    pub fn try_consume_to_two_d_vec<T: FromStr + 'static>(
+        &mut self,
+    ) -> Result<Vec<Vec<T>>, ParseError> {
+        if self.is_empty() {
+            return Err(self.end_of_input::<T>());
+        }
+        let mut result = Vec::new();
+        while let Some(line) = self.next_line() {
+            let v = line.try_to_vec()?;
+            if v.is_empty() {
+                continue;
+            }
+            result.push(v);
+        }
+        Ok(result)
+    }
+    /// Like [`Lines::try_consume_to_vec`], reading each element of the line with `read`.
+    pub fn try_consume_to_vec_with<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        // empty line is skipped
+        while !self.has_next_in_line() {
+            if self.next_line().is_none() {
+                return Err(self.end_of_input::<T>());
+            }
+        }
+        let mut values = Vec::new();
+        while self.has_next_in_line() {
+            values.push(read(self)?);
+        }
+        self.next_line();
+        Ok(values)
+    }
+    /// Returns true if a token is left on the current line, without moving the cursor.
+    pub fn has_next_in_line(&mut self) -> bool {
+        if self.at_limit() {
+            return false;
+        }
+        let mut pos = self.pos;
+        loop {
+            let bytes = self.buf.as_bytes();
+            while pos < bytes.len() {
+                match bytes[pos] {
+                    b'\n' => return false,
+                    b if is_separator(b) => pos += 1,
+                    _ => return true,
+                }
+            }
+            if !self.fill() {
+                return false;
+            }
+        }
+    }
+    /// 1-based line and column of the cursor.
+    pub fn position(&self) -> (usize, usize) {
+        (self.line + 1, self.pos - self.line_start + 1)
+    }
+    /// Shows the input read so far around `line`, with a caret under `column`; both are 1-based.
+    pub fn excerpt(&self, line: usize, column: usize) -> String {
+        let first = line.saturating_sub(2).max(1);
+        // the line after a final line break only exists for an error at the end of input
+        let last = (line + 1).min(self.buf.lines().count().max(line));
+        let shown: Vec<_> = self
+            .buf
+            .split('\n')
+            .enumerate()
+            .map(|(i, text)| (i + 1, text.trim_end_matches('\r')))
+            .skip(first - 1)
+            .take(last + 1 - first)
+            .collect();
+        let width = shown
+            .last()
+            .map_or(1, |(number, _)| number.to_string().len());
+        let mut excerpt = String::new();
+        for (number, text) in shown {
+            excerpt += &format!("{:>width$} | {}\n", number, text);
+            if number == line {
+                // tabs are kept so that the caret lines up with the text above it
+                let indent: String = text
+                    .bytes()
+                    .chain(std::iter::repeat(b' '))
+                    .take(column.saturating_sub(1))
+                    .map(|b| if b == b'\t' { '\t' } else { ' ' })
+                    .collect();
+                excerpt += &format!("{:>width$} | {}^\n", "", indent);
+            }
+        }
+        excerpt
+    }
+    /// An error at the cursor for a value that is computed from the input rather than read
+    /// from it, like `n - 1` rows with `found` being `n = 0`.
+    pub fn invalid_value<T>(&self, found: &str) -> ParseError {
+        let (line, column) = self.position();
+        ParseError::invalid_token::<T>(line, column, found.as_bytes())
+    }
+    /// Panics with `error` and the input around it, after `what` could not be read.
+    #[cold]
+    #[track_caller]
+    pub fn fail(&self, what: &str, error: ParseError) -> ! {
+        panic!(
+            "{}: {}\n{}",
+            what,
+            error,
+            self.excerpt(error.line(), error.column())
+        )
     }
-    fn is_empty(&self) -> bool {
-        self.inner.is_empty()
+    /// Calls `solve`, and if it panics, notes on stderr how far the input was read
+    /// before resuming the panic.
+    pub fn run<T>(&self, name: &str, solve: impl FnOnce() -> T) -> T {
+        match panic::catch_unwind(AssertUnwindSafe(solve)) {
+            Ok(result) => result,
+            Err(payload) => self.resume_panic(name, payload),
+        }
+    }
+    pub fn extend(&mut self, s: &str) {
+        if !self.buf.is_empty() && !self.buf.ends_with('\n') {
+            self.buf.push('\n');
+        }
+        self.buf.push_str(s);
     }
+    // range of the rest of the current line, or of the next line at its start
+    fn line_range(&mut self) -> Option<(usize, usize)> {
+        if self.at_limit() || self.pos == self.buf.len() && !self.fill() {
+            return None;
+        }
+        let start = self.pos;
+        let end = self.buf[start..]
+            .find('\n')
+            .map_or(self.buf.len(), |i| start + i);
+        Some((start, end))
+    }
+    fn is_empty(&mut self) -> bool {
+        self.at_limit() || self.pos == self.buf.len() && !self.fill()
+    }
+    // index of the first line that is not started yet
+    fn next_line_index(&self) -> usize {
+        if self.pos > self.line_start {
+            self.line + 1
+        } else {
+            self.line
+        }
+    }
+    fn at_limit(&self) -> bool {
+        self.line_limit.is_some_and(|limit| self.line >= limit)
+    }
+    // moves the cursor to the next token and returns its range in the buffer
+    fn next_token(&mut self) -> Option<(usize, usize)> {
+        if !self.skip_separators() {
+            return None;
+        }
+        let start = self.pos;
+        let len = self.buf.as_bytes()[start..]
+            .iter()
+            .position(|&b| is_separator(b))
+            .unwrap_or(self.buf.len() - start);
+        self.pos = start + len;
+        Some((start, self.pos))
+    }
+    // moves the cursor to the start of the next token, returns false at the end of input
+    fn skip_separators(&mut self) -> bool {
+        if self.at_limit() {
+            return false;
+        }
+        loop {
+            let bytes = self.buf.as_bytes();
+            let mut pos = self.pos;
+            while pos < bytes.len() {
+                match bytes[pos] {
+                    b'\n' => {
+                        pos += 1;
+                        self.line += 1;
+                        self.line_start = pos;
+                        if self.at_limit() {
+                            self.pos = pos;
+                            return false;
+                        }
+                    }
+                    b if is_separator(b) => pos += 1,
+                    _ => break,
+                }
+            }
+            self.pos = pos;
+            if pos < bytes.len() {
+                return true;
+            }
+            if !self.fill() {
+                return false;
+            }
+        }
+    }
+    // reads one more line from the reader, returns false when nothing was read
+    fn fill(&mut self) -> bool {
+        if self.io_error.is_some()
+            || self
+                .line_limit
+                .is_some_and(|limit| self.read_lines >= limit)
+        {
+            return false;
+        }
+        let Some(reader) = self.reader.as_mut() else {
+            return false;
+        };
+        match reader.read_line(&mut self.buf) {
+            Ok(0) => false,
+            Ok(_) => {
+                self.read_lines += 1;
+                true
+            }
+            Err(e) => {
+                self.io_error = Some(e.to_string());
+                false
+            }
+        }
+    }
+    #[cold]
+    fn resume_panic(&self, name: &str, payload: Box<dyn Any + Send>) -> ! {
+        let (line, column) = self.position();
+        eprintln!(
+            "note: `{}` panicked on the input read up to line {}, column {}:\n{}",
+            name,
+            line,
+            column,
+            self.excerpt(line, column)
+        );
+        panic::resume_unwind(payload)
+    }
+    // a number converted by `convert`, reported as `expected` when it fails
+    fn try_consume_index<T: FromStr + 'static>(
+        &mut self,
+        expected: &'static str,
+        convert: impl FnOnce(T) -> Option<T>,
+    ) -> Result<T, ParseError> {
+        self.try_consume_with(|token| parse_token(token).and_then(convert))
+            .map_err(|e| ParseError { expected, ..e })
+    }
+    // also reports the I/O error, if that is what ended the input
+    fn end_of_input<T>(&self) -> ParseError {
+        ParseError {
+            io_error: self.io_error.clone(),
+            ..ParseError::end_of_input::<T>(self.line + 1, self.pos - self.line_start + 1)
+        }
+    }
+}
+impl Debug for Lines<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lines")
+            .field("buf", &self.buf)
+            .field("pos", &self.pos)
+            .field("line", &self.line)
+            .field("line_limit", &self.line_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+// any ASCII whitespace separates tokens, so `\r` of CRLF endings never ends up in a token
+fn is_separator(b: u8) -> bool {
+    b.is_ascii_whitespace()
+}
+
+fn parse<T: FromStr + 'static>(data: &str, line: usize, column: usize) -> Result<T, ParseError> {
+    parse_token(data).ok_or_else(|| ParseError::invalid_token::<T>(line, column, data.as_bytes()))
 }
 
 #[cfg(test)]
@@ -190,4 +862,443 @@ mod tests {
         let data = line.next_data();
         assert_eq!(data, None);
     }
+    #[test]
+    fn try_consume_reports_invalid_token_position() {
+        let s = "1 2\n3 x 5";
+        let mut lines = Lines::new(s);
+        assert_eq!(lines.try_consume::<isize>().unwrap(), 1);
+        assert_eq!(lines.try_consume::<isize>().unwrap(), 2);
+        assert_eq!(lines.try_consume::<isize>().unwrap(), 3);
+        let err = lines.try_consume::<isize>().unwrap_err();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.column(), 3);
+        assert_eq!(err.token(), Some("x"));
+        assert_eq!(err.expected(), "isize");
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected `isize`, found `x`"
+        );
+    }
+    #[test]
+    fn try_consume_reports_end_of_input() {
+        let s = "1\n";
+        let mut lines = Lines::new(s);
+        assert_eq!(lines.try_consume::<usize>().unwrap(), 1);
+        let err = lines.try_consume::<usize>().unwrap_err();
+        assert!(err.is_end_of_input());
+        assert_eq!(err.line(), 2);
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: expected `usize`, found end of input"
+        );
+    }
+    #[test]
+    fn try_consume_to_vec_reports_invalid_token() {
+        let s = "1 2 3\n4 five 6";
+        let mut lines = Lines::new(s);
+        let v = lines.try_consume_to_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![1, 2, 3]);
+        let err = lines.try_consume_to_vec::<isize>().unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 3));
+        assert_eq!(err.token(), Some("five"));
+        let err = lines.try_consume_to_vec::<isize>().unwrap_err();
+        assert!(err.is_end_of_input());
+    }
+    #[test]
+    fn try_consume_to_two_d_vec() {
+        let s = "1 2\n\n3 4\n";
+        let mut lines = Lines::new(s);
+        let v = lines.try_consume_to_two_d_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![vec![1, 2], vec![3, 4]]);
+        let err = lines.try_consume_to_two_d_vec::<isize>().unwrap_err();
+        assert!(err.is_end_of_input());
+
+        let mut lines = Lines::new("1 2\n3 x");
+        let err = lines.try_consume_to_two_d_vec::<isize>().unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 3));
+    }
+    #[test]
+    fn lines_from_reader() {
+        let reader = std::io::Cursor::new("1 2\n3 4 5\n6 7\n8 9\n");
+        let mut lines = Lines::from_reader(reader);
+        assert_eq!(lines.consume::<isize>().unwrap(), 1);
+        assert_eq!(lines.consume::<isize>().unwrap(), 2);
+        let v = lines.try_consume_to_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![3, 4, 5]);
+        let v = lines.try_consume_to_two_d_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![vec![6, 7], vec![8, 9]]);
+        let err = lines.try_consume::<isize>().unwrap_err();
+        assert!(err.is_end_of_input());
+        assert_eq!((err.line(), err.column()), (5, 1));
+    }
+    #[test]
+    fn lines_from_reader_with_limit() {
+        let reader = std::io::Cursor::new("2\n1 2\n3 4\n5 6\n");
+        let mut lines = Lines::from_reader(reader);
+        let n = lines.consume::<usize>().unwrap();
+        lines.limit_lines(n);
+        let v = lines.try_consume_to_two_d_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![vec![1, 2], vec![3, 4]]);
+        assert_eq!(lines.consume::<isize>(), None);
+    }
+    #[test]
+    fn consume_from_str_types() {
+        use std::net::Ipv4Addr;
+        let mut lines = Lines::new("127.0.0.1 2\n10.0.0.1 10.0.0.2\n");
+        assert_eq!(lines.consume::<Ipv4Addr>(), Some(Ipv4Addr::LOCALHOST));
+        assert_eq!(lines.consume::<usize>(), Some(2));
+        let v = lines.try_consume_to_vec::<Ipv4Addr>().unwrap();
+        assert_eq!(
+            v,
+            vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)]
+        );
+        let e = Lines::new("1.2.3").try_consume::<Ipv4Addr>().unwrap_err();
+        assert_eq!(e.token(), Some("1.2.3"));
+    }
+    #[test]
+    fn tokens_are_separated_by_any_whitespace() {
+        let s = "1  2\t3 \r\n\t4\t \t5  \r\n\r\n6 7\r\n";
+        let mut lines = Lines::new(s);
+        assert_eq!(lines.consume::<isize>().unwrap(), 1);
+        assert_eq!(lines.consume::<isize>().unwrap(), 2);
+        assert_eq!(lines.consume::<isize>().unwrap(), 3);
+        let v = lines.try_consume_to_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![4, 5]);
+        let v = lines.try_consume_to_two_d_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![vec![6, 7]]);
+    }
+    #[test]
+    fn crlf_and_lf_are_the_same() {
+        let lf = Lines::new("a b\nc d\n").consume_to_two_d_vec::<String>();
+        let crlf = Lines::new("a b\r\nc d\r\n").consume_to_two_d_vec::<String>();
+        assert_eq!(lf, crlf);
+
+        let mut lines = Lines::new("1\r\nx\r\n");
+        assert_eq!(lines.consume::<isize>().unwrap(), 1);
+        let err = lines.try_consume::<isize>().unwrap_err();
+        assert_eq!(err.token(), Some("x"));
+        assert_eq!((err.line(), err.column()), (2, 1));
+    }
+    #[test]
+    fn line_to_vec_ignores_surrounding_whitespace() {
+        let line = Line::new(" 1\t2  3 \r");
+        assert_eq!(line.to_vec::<isize>(), vec![1, 2, 3]);
+        let line = Line::new("a  b\t");
+        assert_eq!(line.try_to_vec::<String>().unwrap(), vec!["a", "b"]);
+    }
+    #[test]
+    fn has_next_data_does_not_move_cursor() {
+        let mut lines = Lines::new("1 2\n\n3\n\n");
+        assert_eq!(lines.consume::<isize>().unwrap(), 1);
+        assert!(lines.has_next_data());
+        let v = lines.consume_to_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![2]);
+        assert!(lines.has_next_data());
+        assert_eq!(lines.consume::<isize>().unwrap(), 3);
+        assert!(!lines.has_next_data());
+    }
+    #[test]
+    fn io_error_is_reported_at_the_cursor() {
+        let mut lines = Lines::from_reader(&b"1 2\n\xff\n"[..]);
+        assert_eq!(lines.try_consume::<i32>(), Ok(1));
+        assert_eq!(lines.try_consume::<i32>(), Ok(2));
+        let error = lines.try_consume::<i32>().unwrap_err();
+        assert!(!error.is_end_of_input());
+        assert!(error.io_error().is_some());
+        assert_eq!((error.line(), error.column()), (2, 1));
+        assert!(error
+            .to_string()
+            .starts_with("line 2, column 1: expected `i32`, failed to read the input: "));
+    }
+    #[test]
+    fn consume_chars() {
+        let mut lines = Lines::new("3\n#..#\n.##.\n");
+        assert_eq!(lines.consume::<usize>().unwrap(), 3);
+        let row = lines.read::<Chars>().unwrap();
+        assert_eq!(row, vec!['#', '.', '.', '#']);
+        let row = lines.read::<Bytes>().unwrap();
+        assert_eq!(row, b".##.".to_vec());
+    }
+    #[test]
+    fn peek_does_not_consume() {
+        let mut lines = Lines::new("1 x\n2 3\n");
+        assert_eq!(lines.peek::<isize>(), Some(1));
+        assert_eq!(lines.peek::<String>().unwrap(), "1");
+        assert_eq!(lines.consume::<isize>(), Some(1));
+        let err = lines.try_peek::<isize>().unwrap_err();
+        assert_eq!(err.token(), Some("x"));
+        assert_eq!(lines.consume::<String>().unwrap(), "x");
+        let line = lines.peek_line().unwrap();
+        assert_eq!(line.to_vec::<isize>(), Vec::<isize>::new());
+        assert_eq!(lines.consume::<isize>(), Some(2));
+        let line = lines.peek_line().unwrap();
+        assert_eq!(line.to_vec::<isize>(), vec![3]);
+        assert_eq!(lines.consume_to_vec::<isize>(), Some(vec![3]));
+        assert!(lines.peek_line().is_none());
+        assert_eq!(lines.peek::<isize>(), None);
+    }
+    #[test]
+    fn restore_rewinds_to_checkpoint() {
+        let reader = std::io::Cursor::new("2 a\n1 5\n");
+        let mut lines = Lines::from_reader(reader);
+        let checkpoint = lines.checkpoint();
+        assert_eq!(lines.consume::<isize>(), Some(2));
+        assert_eq!(lines.consume::<isize>(), None);
+        let v = lines.consume_to_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![1, 5]);
+        lines.restore(checkpoint);
+        let v = lines.try_consume_to_two_d_vec::<String>();
+        assert_eq!(v.unwrap(), vec![vec!["2", "a"], vec!["1", "5"]]);
+    }
+    #[test]
+    fn branch_on_query_type() {
+        let mut lines = Lines::new("1 3 4\n2 5\n");
+        let mut result = Vec::new();
+        while let Some(kind) = lines.peek::<usize>() {
+            match kind {
+                1 => result.push(lines.consume_to_vec::<isize>().unwrap()[1..].iter().sum()),
+                _ => result.push(-lines.consume_to_vec::<isize>().unwrap()[1]),
+            }
+        }
+        assert_eq!(result, vec![7, -5]);
+    }
+    #[test]
+    fn consume_n_ignores_line_layout() {
+        let mut lines = Lines::new("3\n1 2 3\n3\n1\n2\n3\n");
+        let n = lines.consume::<usize>().unwrap();
+        assert_eq!(lines.consume_n::<isize>(n).unwrap(), vec![1, 2, 3]);
+        let n = lines.consume::<usize>().unwrap();
+        assert_eq!(lines.consume_n::<isize>(n).unwrap(), vec![1, 2, 3]);
+        let err = lines.try_consume_n::<isize>(1).unwrap_err();
+        assert!(err.is_end_of_input());
+        assert_eq!(lines.consume_n::<isize>(0).unwrap(), vec![]);
+    }
+    #[test]
+    fn consume_columns() {
+        let mut lines = Lines::new("3\n1 a\n2 b\n3 c\n4 x\n");
+        let n = lines.consume::<usize>().unwrap();
+        let (a, b) = lines.consume_columns::<(isize, char)>(n).unwrap();
+        assert_eq!(a, vec![1, 2, 3]);
+        assert_eq!(b, vec!['a', 'b', 'c']);
+        let err = lines.try_consume_columns::<(isize, isize)>(1).unwrap_err();
+        assert_eq!((err.line(), err.column()), (5, 3));
+    }
+    #[test]
+    fn read_readable() {
+        let mut lines = Lines::new("1 2\n3 4 5\nx\n");
+        let p = lines.read::<(usize, (i64, char))>();
+        assert_eq!(p.unwrap(), (1, (2, '3')));
+        let a = lines.read::<[[u8; 1]; 2]>();
+        assert_eq!(a.unwrap(), [[4], [5]]);
+        let err = lines.read::<(usize,)>().unwrap_err();
+        assert_eq!(err.token(), Some("x"));
+    }
+    #[test]
+    fn consume_tag() {
+        let mut lines = Lines::new("2 x\n7\n");
+        assert_eq!(lines.consume_tag::<()>(&["1", "2"]), Some(1));
+        assert_eq!(lines.consume_tag::<()>(&["x"]), Some(0));
+        let err = lines.try_consume_tag::<u8>(&["1", "2"]).unwrap_err();
+        assert_eq!((err.line(), err.column(), err.token()), (2, 1, Some("7")));
+        assert!(lines
+            .try_consume_tag::<u8>(&["1"])
+            .unwrap_err()
+            .is_end_of_input());
+    }
+    #[test]
+    fn consume_one_based_index() {
+        let mut lines = Lines::new("1 3\n-1\n0");
+        assert_eq!(lines.try_consume_usize1(), Ok(0));
+        assert_eq!(lines.try_consume_usize1(), Ok(2));
+        assert_eq!(lines.try_consume_isize1(), Ok(-2));
+        let err = lines.try_consume_usize1().unwrap_err();
+        assert_eq!(
+            (err.line(), err.token(), err.expected()),
+            (3, Some("0"), "Usize1")
+        );
+        assert!(lines.try_consume_isize1().unwrap_err().is_end_of_input());
+    }
+    #[test]
+    fn consume_to_vec_with() {
+        let mut lines = Lines::new("\n1 2 3\n4\n");
+        assert!(!lines.has_next_in_line());
+        let v = lines.try_consume_to_vec_with(Lines::try_consume_usize1);
+        assert_eq!(v, Ok(vec![0, 1, 2]));
+        assert!(lines.has_next_in_line());
+        assert_eq!(lines.consume::<u8>(), Some(4));
+        assert!(!lines.has_next_in_line());
+        let err = lines.try_consume_to_vec_with(Lines::try_consume_usize1);
+        assert!(err.unwrap_err().is_end_of_input());
+    }
+    #[test]
+    fn read_one_based_markers() {
+        let mut lines = Lines::new("1 2\n3 4 5\n0\n");
+        assert_eq!(lines.read::<Usize1>(), Ok(0));
+        assert_eq!(lines.read::<(Usize1,)>(), Ok((1,)));
+        assert_eq!(lines.read::<[Isize1; 2]>(), Ok([2, 3]));
+        assert_eq!(lines.read::<Option<Usize1>>(), Ok(Some(4)));
+        let err = lines.read::<Usize1>().unwrap_err();
+        assert_eq!((err.token(), err.expected()), (Some("0"), "Usize1"));
+        let mut lines = Lines::new("2 a\n1 b\n");
+        let (a, b) = lines.try_consume_columns::<(Usize1, char)>(2).unwrap();
+        assert_eq!((a, b), (vec![1, 0], vec!['a', 'b']));
+    }
+    #[test]
+    fn read_option() {
+        let mut lines = Lines::new("1 2\n3\n");
+        assert_eq!(lines.read::<(u8, Option<u8>)>(), Ok((1, Some(2))));
+        assert_eq!(lines.read::<Option<u8>>(), Ok(None));
+        lines.next_line();
+        assert_eq!(lines.read::<(u8, Option<u8>)>(), Ok((3, None)));
+        assert_eq!(lines.read::<Option<u8>>(), Ok(None));
+    }
+    #[test]
+    fn limit_lines_of_a_string() {
+        let mut lines = Lines::new("2 9\n1 2\n3 4\n5 6\n");
+        let n = lines.consume::<usize>().unwrap();
+        lines.limit_lines(n);
+        // the rest of the current line is not counted
+        assert_eq!(lines.consume::<usize>(), Some(9));
+        let v = lines.try_consume_to_two_d_vec::<isize>();
+        assert_eq!(v.unwrap(), vec![vec![1, 2], vec![3, 4]]);
+        assert!(!lines.has_next_data());
+        assert_eq!(lines.next_line().map(|line| line.to_vec::<u8>()), None);
+    }
+    #[test]
+    fn within_lines() {
+        let reader = std::io::Cursor::new("1 2\n3 4 5\n6\n7 8\n");
+        let mut lines = Lines::from_reader(reader);
+        lines.limit_lines(3);
+        let v = lines.within_lines(2, |lines| {
+            let mut v = Vec::new();
+            while let Ok(x) = lines.try_consume::<u8>() {
+                v.push(x);
+            }
+            v
+        });
+        assert_eq!(v, vec![1, 2, 3, 4, 5]);
+        // leftovers are skipped and the outer limit is kept
+        let first = lines.within_lines(5, |lines| lines.consume::<u8>());
+        assert_eq!(first, Some(6));
+        assert_eq!(lines.consume::<u8>(), None);
+    }
+    #[test]
+    fn finish_rejects_leftover_input() {
+        let mut lines = Lines::new("1 2\n3 4\n");
+        lines.limit_lines(1);
+        lines.try_consume_n::<i32>(2).unwrap();
+        assert!(lines.finish_lines(LineLimit(None)).is_ok());
+        let error = lines.finish().unwrap_err();
+        assert_eq!(
+            (error.line(), error.column(), error.token()),
+            (2, 1, Some("3"))
+        );
+
+        let mut lines = Lines::new("1 2 3");
+        lines.limit_lines(1);
+        lines.try_consume_n::<i32>(2).unwrap();
+        let error = lines.finish().unwrap_err();
+        assert_eq!((error.column(), error.token()), (5, Some("3")));
+
+        let mut lines = Lines::new("1 2\n\n");
+        lines.try_consume_n::<i32>(2).unwrap();
+        assert!(lines.finish().is_ok());
+    }
+    #[test]
+    fn finish_rejects_missing_lines() {
+        let mut lines = Lines::new("1\n2");
+        let outer = lines.enter_lines(3);
+        assert_eq!(lines.try_consume_to_two_d_vec::<i32>().unwrap().len(), 2);
+        let error = lines.finish_lines(outer).unwrap_err();
+        assert!(error.is_end_of_input());
+        assert_eq!(error.expected(), "another line");
+
+        let mut lines = Lines::new("1\n2");
+        lines.limit_lines(2);
+        lines.try_consume_to_two_d_vec::<i32>().unwrap();
+        assert!(lines.finish().is_ok());
+    }
+    #[test]
+    fn line_reads_after_a_token() {
+        let input = "1 2\n3 4\n\n5 6\n7\n";
+
+        let mut lines = Lines::new(input);
+        assert_eq!(lines.try_consume::<i32>(), Ok(1));
+        assert_eq!(lines.try_consume_rest_of_line::<i32>(), Ok(vec![2]));
+        assert_eq!(lines.try_consume::<i32>(), Ok(3));
+        assert_eq!(lines.try_consume::<i32>(), Ok(4));
+        assert_eq!(lines.try_consume_rest_of_line::<i32>(), Ok(vec![]));
+        // an empty line is a line
+        assert_eq!(lines.try_consume_rest_of_line::<i32>(), Ok(vec![]));
+        assert_eq!(lines.try_consume_rest_of_line::<i32>(), Ok(vec![5, 6]));
+
+        let mut lines = Lines::new(input);
+        assert_eq!(lines.try_consume::<i32>(), Ok(1));
+        assert_eq!(lines.try_consume_next_line::<i32>(), Ok(vec![3, 4]));
+        assert_eq!(lines.try_consume_next_line::<i32>(), Ok(vec![]));
+
+        let mut lines = Lines::new(input);
+        assert_eq!(lines.try_consume::<i32>(), Ok(1));
+        assert_eq!(lines.try_consume::<i32>(), Ok(2));
+        // nothing is left of the first line and the empty one is skipped
+        assert_eq!(
+            lines.try_consume_rows::<i32>(2),
+            Ok(vec![vec![3, 4], vec![5, 6]])
+        );
+        assert_eq!(lines.try_consume_to_vec::<i32>(), Ok(vec![7]));
+        assert!(lines
+            .try_consume_next_line::<i32>()
+            .unwrap_err()
+            .is_end_of_input());
+
+        let mut lines = Lines::new(input);
+        assert_eq!(lines.try_consume::<i32>(), Ok(1));
+        let v = lines.try_consume_rest_of_line_with(Lines::try_consume_usize1);
+        assert_eq!(v, Ok(vec![1]));
+        let v = lines.try_consume_next_line_with(Lines::try_consume_usize1);
+        assert_eq!(v, Ok(vec![2, 3]));
+        let v = lines.try_consume_next_line_with(Lines::try_consume_usize1);
+        assert_eq!(v, Ok(vec![]));
+    }
+    #[test]
+    fn excerpt_points_at_the_error() {
+        let mut lines = Lines::new("3\n1 2\n4 x 6\n7\n8\n");
+        lines.try_consume_n::<i32>(4).unwrap();
+        let error = lines.try_consume::<i32>().unwrap_err();
+        assert_eq!((error.line(), error.column()), (3, 3));
+        assert_eq!(
+            lines.excerpt(error.line(), error.column()),
+            "1 | 3\n2 | 1 2\n3 | 4 x 6\n  |   ^\n4 | 7\n"
+        );
+        let mut lines = Lines::new("1\n");
+        lines.try_consume::<i32>().unwrap();
+        assert_eq!(lines.excerpt(1, 2), "1 | 1\n  |  ^\n");
+        let error = lines.try_consume::<i32>().unwrap_err();
+        assert_eq!(
+            lines.excerpt(error.line(), error.column()),
+            "1 | 1\n2 | \n  | ^\n"
+        );
+        // the end of input is past the last line
+        let mut lines = Lines::new("1\t2");
+        lines.try_consume_n::<i32>(2).unwrap();
+        let error = lines.try_consume::<i32>().unwrap_err();
+        assert_eq!(
+            lines.excerpt(error.line(), error.column()),
+            "1 | 1\t2\n  |  \t ^\n"
+        );
+    }
+    #[test]
+    fn fail_reports_the_input() {
+        let mut lines = Lines::new("1 x");
+        lines.try_consume::<i32>().unwrap();
+        let error = lines.try_consume::<i32>().unwrap_err();
+        let payload =
+            panic::catch_unwind(AssertUnwindSafe(|| lines.fail("failed to read `b`", error)))
+                .unwrap_err();
+        assert_eq!(
+            payload.downcast_ref::<String>().unwrap(),
+            "failed to read `b`: line 1, column 3: expected `i32`, found `x`\n1 | 1 x\n  |   ^\n"
+        );
+        assert_eq!(lines.run("solve", || 2), 2);
+    }
 }
@@ -0,0 +1,125 @@
+use std::{marker::PhantomData, str::FromStr};
+
+use crate::{Bytes, Chars, Isize1, Lines, ParseError, Usize1};
+
+/// A value that can read itself from the next tokens of [`Lines`].
+///
+/// Numbers, `bool`, `char` and `String` read a single token like [`Lines::try_consume`],
+/// tuples and arrays read their elements in order. `#[derive(Readable)]` implements it for
+/// structs by reading the fields in declaration order, so `Vec<Edge>` works as a `#[pte]` argument.
+pub trait Readable: Sized {
+    /// The value that is read: `Self`, except for markers like [`Usize1`] that read a `usize`
+    /// or [`Chars`] that reads a `Vec<char>`.
+    type Output;
+    fn read(lines: &mut Lines<'_>) -> Result<Self::Output, ParseError>;
+}
+
+macro_rules! impl_readable_for_token {
+    ($($t:ty),*) => {$(
+        impl Readable for $t {
+            type Output = Self;
+            #[inline]
+            fn read(lines: &mut Lines<'_>) -> Result<Self, ParseError> {
+                lines.try_consume()
+            }
+        }
+    )*};
+}
+impl_readable_for_token!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char, String
+);
+
+// a token like `#..#` read as its characters
+impl Readable for Chars {
+    type Output = Vec<char>;
+    fn read(lines: &mut Lines<'_>) -> Result<Vec<char>, ParseError> {
+        lines.try_consume_with(|token| Some(token.chars().collect()))
+    }
+}
+impl Readable for Bytes {
+    type Output = Vec<u8>;
+    fn read(lines: &mut Lines<'_>) -> Result<Vec<u8>, ParseError> {
+        lines.try_consume_with(|token| Some(token.as_bytes().to_vec()))
+    }
+}
+
+impl Readable for Usize1 {
+    type Output = usize;
+    fn read(lines: &mut Lines<'_>) -> Result<usize, ParseError> {
+        lines.try_consume_usize1()
+    }
+}
+impl Readable for Isize1 {
+    type Output = isize;
+    fn read(lines: &mut Lines<'_>) -> Result<isize, ParseError> {
+        lines.try_consume_isize1()
+    }
+}
+
+macro_rules! impl_readable_for_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: Readable),*> Readable for ($($t,)*) {
+            type Output = ($($t::Output,)*);
+            fn read(lines: &mut Lines<'_>) -> Result<Self::Output, ParseError> {
+                Ok(($($t::read(lines)?,)*))
+            }
+        }
+    };
+}
+impl_readable_for_tuple!(A);
+impl_readable_for_tuple!(A, B);
+impl_readable_for_tuple!(A, B, C);
+impl_readable_for_tuple!(A, B, C, D);
+impl_readable_for_tuple!(A, B, C, D, E);
+impl_readable_for_tuple!(A, B, C, D, E, F);
+
+/// `None` when the current line has no more tokens, for optional trailing fields.
+impl<T: Readable> Readable for Option<T> {
+    type Output = Option<T::Output>;
+    fn read(lines: &mut Lines<'_>) -> Result<Self::Output, ParseError> {
+        if lines.has_next_in_line() {
+            T::read(lines).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: Readable, const N: usize> Readable for [T; N] {
+    type Output = [T::Output; N];
+    fn read(lines: &mut Lines<'_>) -> Result<Self::Output, ParseError> {
+        let mut values = Vec::with_capacity(N);
+        for _ in 0..N {
+            values.push(T::read(lines)?);
+        }
+        Ok(values
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("exactly N values are read")))
+    }
+}
+
+// `#[pte]` reads a type it does not know through `(&Reader::<T>::NEW).read_value(..)`:
+// method lookup takes `ReadReadable` if `T: Readable`, and `ReadFromStr` one autoref later
+#[doc(hidden)]
+pub struct Reader<T>(PhantomData<T>);
+impl<T> Reader<T> {
+    pub const NEW: Self = Reader(PhantomData);
+}
+#[doc(hidden)]
+pub trait ReadReadable<T> {
+    fn read_value(&self, lines: &mut Lines<'_>) -> Result<T, ParseError>;
+}
+impl<T: Readable<Output = T>> ReadReadable<T> for Reader<T> {
+    fn read_value(&self, lines: &mut Lines<'_>) -> Result<T, ParseError> {
+        T::read(lines)
+    }
+}
+#[doc(hidden)]
+pub trait ReadFromStr<T> {
+    fn read_value(&self, lines: &mut Lines<'_>) -> Result<T, ParseError>;
+}
+impl<T: FromStr + 'static> ReadFromStr<T> for &Reader<T> {
+    fn read_value(&self, lines: &mut Lines<'_>) -> Result<T, ParseError> {
+        lines.try_consume()
+    }
+}
@@ -0,0 +1,140 @@
+use std::{any::Any, str::FromStr};
+
+/// Converts a single whitespace-free token of the input into an integer, straight from the bytes.
+pub(crate) trait FromToken: Sized {
+    fn from_token(token: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_from_token_for_unsigned {
+    ($($t:ty),*) => {$(
+        impl FromToken for $t {
+            #[inline]
+            fn from_token(token: &[u8]) -> Option<Self> {
+                let digits = match token {
+                    [b'+', rest @ ..] => rest,
+                    _ => token,
+                };
+                if digits.is_empty() {
+                    return None;
+                }
+                let mut n: $t = 0;
+                for &b in digits {
+                    let d = b.wrapping_sub(b'0');
+                    if d > 9 {
+                        return None;
+                    }
+                    n = n.checked_mul(10)?.checked_add(d as $t)?;
+                }
+                Some(n)
+            }
+        }
+    )*};
+}
+impl_from_token_for_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_from_token_for_signed {
+    ($($t:ty),*) => {$(
+        impl FromToken for $t {
+            #[inline]
+            fn from_token(token: &[u8]) -> Option<Self> {
+                let (negative, digits) = match token {
+                    [b'-', rest @ ..] => (true, rest),
+                    [b'+', rest @ ..] => (false, rest),
+                    _ => (false, token),
+                };
+                if digits.is_empty() {
+                    return None;
+                }
+                // accumulate toward the sign so that MIN does not overflow
+                let mut n: $t = 0;
+                for &b in digits {
+                    let d = b.wrapping_sub(b'0');
+                    if d > 9 {
+                        return None;
+                    }
+                    n = n.checked_mul(10)?;
+                    n = if negative {
+                        n.checked_sub(d as $t)?
+                    } else {
+                        n.checked_add(d as $t)?
+                    };
+                }
+                Some(n)
+            }
+        }
+    )*};
+}
+impl_from_token_for_signed!(i8, i16, i32, i64, i128, isize);
+
+/// Parses `token` like `T::from_str`, with the byte-level scanner when `T` is an integer type.
+///
+/// `T` is matched against the integer types by `TypeId` through `dyn Any` at run time, a few
+/// comparisons per token, and other types fall through to `FromStr`.
+#[inline]
+pub(crate) fn parse_token<T: FromStr + 'static>(token: &str) -> Option<T> {
+    let mut value: Option<T> = None;
+    let slot: &mut dyn Any = &mut value;
+    macro_rules! scan {
+        ($($t:ty),*) => {$(
+            if let Some(slot) = slot.downcast_mut::<Option<$t>>() {
+                *slot = <$t>::from_token(token.as_bytes());
+                return value;
+            }
+        )*};
+    }
+    scan!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    token.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn unsigned_from_token() {
+        assert_eq!(usize::from_token(b"0"), Some(0));
+        assert_eq!(usize::from_token(b"+42"), Some(42));
+        assert_eq!(u8::from_token(b"255"), Some(255));
+        assert_eq!(u8::from_token(b"256"), None);
+        assert_eq!(u64::from_token(b"-1"), None);
+        assert_eq!(u64::from_token(b""), None);
+        assert_eq!(u64::from_token(b"1a"), None);
+    }
+    #[test]
+    fn signed_from_token() {
+        assert_eq!(i64::from_token(b"-42"), Some(-42));
+        assert_eq!(i64::from_token(b"+42"), Some(42));
+        assert_eq!(i8::from_token(b"-128"), Some(i8::MIN));
+        assert_eq!(i8::from_token(b"127"), Some(i8::MAX));
+        assert_eq!(i8::from_token(b"128"), None);
+        assert_eq!(i64::from_token(b"-"), None);
+        assert_eq!(i64::from_token(b"--1"), None);
+    }
+    #[test]
+    fn same_as_from_str() {
+        for s in [
+            "0",
+            "-0",
+            "+7",
+            "9223372036854775807",
+            "-9223372036854775808",
+            "x",
+            "",
+        ] {
+            assert_eq!(
+                i64::from_token(s.as_bytes()),
+                s.parse::<i64>().ok(),
+                "{}",
+                s
+            );
+        }
+    }
+    #[test]
+    fn parse_token_falls_back_to_from_str() {
+        assert_eq!(parse_token::<i64>("-42"), Some(-42));
+        assert_eq!(parse_token::<u8>("256"), None);
+        assert_eq!(parse_token::<f64>("1.5"), Some(1.5));
+        assert_eq!(parse_token::<char>("#"), Some('#'));
+        assert_eq!(parse_token::<String>("abc"), Some("abc".to_string()));
+        assert_eq!(parse_token::<bool>("1"), None);
+    }
+}
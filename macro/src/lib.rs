@@ -1,9 +1,11 @@
 extern crate proc_macro;
 
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Parser},
     spanned::Spanned,
+    visit::Visit,
+    visit_mut::VisitMut,
     Ident, Type,
 };
 
@@ -15,387 +17,1507 @@ pub fn pte(
     pte_impl(attr.into(), item.into()).into()
 }
 
+#[proc_macro_derive(Readable, attributes(tag, one_based, chars, bytes))]
+pub fn derive_readable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_readable_impl(input.into()).into()
+}
+
 fn pte_impl(
     attr: proc_macro2::TokenStream,
     item: proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
+    expand_pte(attr, item.clone()).unwrap_or_else(|e| {
+        // keep the function so that the error is the only one reported
+        let item = match syn::parse2(item.clone()) {
+            Ok(item) => declare_item(item),
+            Err(_) => item,
+        };
+        let error = e.to_compile_error();
+        quote! {
+            #item
+
+            fn main() {
+                #error
+            }
+        }
+    })
+}
+
+fn expand_pte(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
     let dependencies = dependencies();
-    let fn_sig = fn_parse.parse2(item).unwrap();
-    let attr = attr.to_string();
-    let parser = PteAttrParser::new(&attr);
-    let consume_lines = consume_lines(&fn_sig, parser);
+    let fn_sig = fn_parse.parse2(item)?;
+    let attr: PteAttr = syn::parse2(attr)?;
+    let consume_lines = consume_lines(&fn_sig, &attr)?;
+    let finish = if attr.is_strict() {
+        quote! {
+            lines.finish().unwrap_or_else(|e| lines.fail("the input does not match the arguments", e));
+        }
+    } else {
+        quote! {}
+    };
     let fn_sig_declare = fn_declare(&fn_sig);
     let fn_sig_execute = fn_execute(&fn_sig);
+    let fn_name = fn_sig.name().to_string();
 
-    quote! {
-        #dependencies
-
+    Ok(quote! {
         #fn_sig_declare
 
         fn main() {
+            #dependencies
             #consume_lines
-            let result = #fn_sig_execute
+            #finish
+            let result = lines.run(#fn_name, || #fn_sig_execute);
             println!("{}", result);
         }
-    }
+    })
 }
 
 fn dependencies() -> proc_macro2::TokenStream {
     quote! {
         use pte::{
             Lines,
+            Readable,
+            __private::{ReadFromStr, ReadReadable, Reader},
+        };
+    }
+}
+
+fn derive_readable_impl(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let input: syn::DeriveInput = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error(),
+    };
+    let name = &input.ident;
+    let body = match &input.data {
+        syn::Data::Struct(data) => {
+            let value = match read_fields(quote! { #name }, &data.fields) {
+                Ok(value) => value,
+                Err(e) => return e.to_compile_error(),
+            };
+            quote! { Ok(#value) }
+        }
+        syn::Data::Enum(data) => match read_variants(name, data) {
+            Ok(body) => body,
+            Err(e) => return e.to_compile_error(),
+        },
+        syn::Data::Union(_) => {
+            return syn::Error::new(input.span(), "`Readable` cannot be derived for unions")
+                .to_compile_error();
+        }
+    };
+    let mut generics = input.generics.clone();
+    // a marker like `Usize1` reads another type, so it cannot be a type argument
+    for param in generics.type_params_mut() {
+        let ident = &param.ident;
+        param
+            .bounds
+            .push(syn::parse_quote!(Readable<Output = #ident>));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        const _: () = {
+            use pte::{Lines, ParseError, Readable, __private::{ReadFromStr, ReadReadable, Reader}};
+            impl #impl_generics Readable for #name #ty_generics #where_clause {
+                type Output = Self;
+                fn read(lines: &mut Lines<'_>) -> Result<Self, ParseError> {
+                    #body
+                }
+            }
+        };
+    }
+}
+// `path { .. }` or `path(..)` with the fields read in declaration order
+fn read_fields(
+    path: proc_macro2::TokenStream,
+    fields: &syn::Fields,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let lines = quote! { lines };
+    let finish = quote! { ? };
+    let values = fields
+        .iter()
+        .map(|field| {
+            let marked = field.attrs.iter().find(|attr| {
+                MARKER_ATTRS
+                    .iter()
+                    .any(|(name, _)| attr.path.is_ident(name))
+            });
+            // a marked field is read like its marker, which checks the type too
+            let read = match marked {
+                Some(attr) => read_marked(&field.ty, attr)?,
+                None => {
+                    check_type(&field.ty, true)?;
+                    if let Some(marker) = find_marker(&field.ty) {
+                        return Err(syn::Error::new_spanned(
+                            marker,
+                            format!(
+                                "`{}` holds no value, so declare the field as `{}` with `#[{}]`",
+                                marker.to_token_stream(),
+                                type_display(&marker_output(marker)),
+                                marker_attr(marker)
+                            ),
+                        ));
+                    }
+                    read_tokens(&field.ty, &lines, &finish)
+                }
+            };
+            Ok(match &field.ident {
+                Some(ident) => quote! { #ident: #read },
+                None => read,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(match fields {
+        syn::Fields::Named(_) => quote! { #path { #(#values),* } },
+        syn::Fields::Unnamed(_) => quote! { #path(#(#values),*) },
+        syn::Fields::Unit => path,
+    })
+}
+// `#[one_based]` on a `usize` or `isize` field reads it like `Usize1` or `Isize1`,
+// `#[chars]` on a `Vec<char>` like `Chars` and `#[bytes]` on a `Vec<u8>` like `Bytes`
+const MARKER_ATTRS: [(&str, &str); 4] = [
+    ("one_based", "Usize1"),
+    ("one_based", "Isize1"),
+    ("chars", "Chars"),
+    ("bytes", "Bytes"),
+];
+fn marker_attr(marker: &Type) -> &'static str {
+    MARKER_ATTRS
+        .iter()
+        .find(|(_, name)| marker_ident(marker).is_some_and(|ident| ident == name))
+        .map_or("", |(attr, _)| attr)
+}
+fn read_marked(ty: &Type, attr: &syn::Attribute) -> syn::Result<proc_macro2::TokenStream> {
+    let markers = MARKER_ATTRS
+        .iter()
+        .filter(|(name, _)| attr.path.is_ident(name))
+        .map(|(_, marker)| Ident::new(marker, attr.span()))
+        .collect::<Vec<_>>();
+    let outputs = markers.iter().map(marker_type).collect::<Vec<_>>();
+    match markers.iter().zip(&outputs).find(|(_, output)| {
+        output.to_token_stream().to_string() == ty.to_token_stream().to_string()
+    }) {
+        Some((marker, _)) => Ok(quote! { <pte::#marker as Readable>::read(lines)? }),
+        None => Err(syn::Error::new_spanned(
+            attr,
+            format!(
+                "`{}` expects a {} field",
+                attr.to_token_stream().to_string().replace(' ', ""),
+                outputs
+                    .iter()
+                    .map(|output| format!("`{}`", type_display(output)))
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ),
+        )),
+    }
+}
+// the leading token picks the variant by its `#[tag(..)]`
+fn read_variants(name: &Ident, data: &syn::DataEnum) -> syn::Result<proc_macro2::TokenStream> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut arms = Vec::new();
+    for (i, variant) in data.variants.iter().enumerate() {
+        let Some(attr) = variant.attrs.iter().find(|attr| attr.path.is_ident("tag")) else {
+            return Err(syn::Error::new(
+                variant.span(),
+                "each variant needs a tag like `#[tag(1)]`",
+            ));
+        };
+        let tag = match attr.parse_args::<syn::Lit>()? {
+            syn::Lit::Int(lit) => lit.base10_digits().to_string(),
+            syn::Lit::Str(lit) => lit.value(),
+            syn::Lit::Char(lit) => lit.value().to_string(),
+            lit => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "expected an integer, string or char tag",
+                ))
+            }
         };
+        if tags.contains(&tag) {
+            return Err(syn::Error::new(
+                attr.span(),
+                format!("duplicate tag `{}`", tag),
+            ));
+        }
+        tags.push(tag);
+        let ident = &variant.ident;
+        let value = read_fields(quote! { #name::#ident }, &variant.fields)?;
+        let i = proc_macro2::Literal::usize_unsuffixed(i);
+        arms.push(quote! { #i => #value, });
     }
+    Ok(quote! {
+        let tag = lines.try_consume_tag::<Self>(&[#(#tags),*])?;
+        Ok(match tag {
+            #(#arms)*
+            _ => unreachable!(),
+        })
+    })
 }
 
 fn fn_execute(fn_sig: &FunctionSignature) -> proc_macro2::TokenStream {
     let name = fn_sig.name();
     let args = fn_sig.args();
-    let args = args.iter().map(|(name, _)| {
-        quote! { #name }
-    });
+    let args = args.iter().map(Arg::value);
     quote! {
-        #name(#(#args),*);
+        #name(#(#args),*)
     }
 }
 
+// the function as written, only without the argument attributes for pte
+// and with the markers like `Usize1` declared as the `usize` they are read as
 fn fn_declare(fn_sig: &FunctionSignature) -> proc_macro2::TokenStream {
-    let name = fn_sig.name();
-    let args = fn_sig.args();
-    let args = args.iter().map(|(name, ty)| {
-        quote! { #name: #ty }
-    });
-    let ty = fn_sig.return_type();
-    let body = fn_sig.block();
-    quote! {
-        fn #name(#(#args),*) #ty #body
+    declare_item(fn_sig.item().clone())
+}
+fn declare_item(mut item: syn::ItemFn) -> proc_macro2::TokenStream {
+    for input in &mut item.sig.inputs {
+        if let syn::FnArg::Typed(arg) = input {
+            arg.attrs.retain(|attr| !Arg::is_pte_attr(attr));
+            *arg.ty = marker_output(&arg.ty);
+        }
     }
+    item.into_token_stream()
 }
 
 fn consume_lines(
     fn_sig: &FunctionSignature,
-    parse_attr: PteAttrParser,
-) -> proc_macro2::TokenStream {
-    if parse_attr.exist_row_num_at_input() {
-        let input_ref = parse_attr.get_input_ref().unwrap();
-        return consume_lines_from_input(fn_sig, input_ref);
-    }
-    if parse_attr.exist_row_num() {
-        let n = parse_attr.get_row_num().unwrap();
-        return consume_lines_from_row_num(fn_sig, n as usize);
+    attr: &PteAttr,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let strict = attr.is_strict();
+    match attr.row() {
+        Some(Row::Input(input_ref)) => consume_lines_from_input(fn_sig, *input_ref, strict),
+        Some(Row::Count(n)) => consume_lines_from_row_num(fn_sig, *n, strict),
+        Some(Row::Var(var_name)) => consume_lines_from_var_name(fn_sig, var_name, strict),
+        Some(Row::Expr(expr)) => consume_lines_from_expr(fn_sig, expr, strict),
+        // explicit lengths tell how much to read, so the default single row does not apply
+        None if fn_sig.args().iter().any(Arg::is_sized) => default_consume_lines(fn_sig, strict),
+        None => consume_lines_from_row_num(fn_sig, PteAttr::DEFAULT_ROW_NUM, strict),
     }
-    if parse_attr.exist_row_num_at_var_name() {
-        let var_name = parse_attr.get_var_name().unwrap();
-        return consume_lines_from_var_name(fn_sig, var_name);
-    }
-    default_consume_lines(fn_sig)
 }
 
-fn default_consume_lines(fn_sig: &FunctionSignature) -> proc_macro2::TokenStream {
-    let result = fn_sig
-        .args()
-        .iter()
-        .map(|(name, ty)| {
-            arg_to_consume_line_token_stream(
-                name,
-                ty,
-                &syn::Ident::new("lines", fn_sig.name.span()),
-            )
-        })
-        .collect::<Vec<_>>();
-    quote! {
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
-        let mut lines = Lines::new(&input);
+fn default_consume_lines(
+    fn_sig: &FunctionSignature,
+    strict: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let lines_ident = syn::Ident::new("lines", fn_sig.name().span());
+    let result = consume_args(fn_sig.args(), &lines_ident, strict, false)?;
+    Ok(quote! {
+        let mut lines = Lines::from_reader(std::io::stdin().lock());
 
         #(#result)*
-    }
+    })
 }
 
 fn consume_lines_from_input(
     fn_sig: &FunctionSignature,
     input_num: usize,
-) -> proc_macro2::TokenStream {
-    let result = fn_sig
-        .args()
-        .iter()
-        .map(|(name, ty)| {
-            arg_to_consume_line_token_stream(
-                name,
-                ty,
-                &syn::Ident::new("lines", fn_sig.name.span()),
-            )
-        })
-        .collect::<Vec<_>>();
-    let input_ref = proc_macro2::Literal::usize_unsuffixed(input_num);
-
-    quote! {
-        let mut first_line = String::new();
-        std::io::stdin().read_line(&mut first_line).unwrap();
-
-        let row_num = first_line.split_whitespace().nth(#input_ref).unwrap().parse::<usize>().unwrap();
-
-        let mut input = String::new();
-        for _ in 0..row_num {
-            std::io::stdin().read_line(&mut input).unwrap();
+    strict: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let lines_ident = syn::Ident::new("lines", fn_sig.name().span());
+    let result = consume_args(fn_sig.args(), &lines_ident, strict, false)?;
+    // tokens before the referenced one are skipped
+    let skip = if input_num > 0 {
+        let input_ref = proc_macro2::Literal::usize_unsuffixed(input_num);
+        quote! {
+            for _ in 0..#input_ref {
+                lines.next_data();
+            }
+        }
+    } else {
+        quote! {}
+    };
+    // the number is looked up on the first line only, a short line is an error
+    // and so is a token after it in strict mode
+    let outer = Ident::new("outer", proc_macro2::Span::mixed_site());
+    let leave = if strict {
+        quote! {
+            lines.finish_lines(#outer).unwrap_or_else(|e| lines.fail("the input does not match the arguments", e));
         }
-        let mut lines = Lines::new(&input);
+    } else {
+        quote! { lines.leave_lines(#outer); }
+    };
+    Ok(quote! {
+        let mut lines = Lines::from_reader(std::io::stdin().lock());
+        let #outer = lines.enter_lines(1);
+        #skip
+        let row_num = lines.try_consume::<usize>().unwrap_or_else(|e| lines.fail("failed to read the number of rows", e));
+        #leave
+        lines.limit_lines(row_num);
         #(#result)*
-    }
+    })
 }
-fn consume_lines_from_row_num(fn_sig: &FunctionSignature, n: usize) -> proc_macro2::TokenStream {
-    let result = fn_sig
-        .args()
-        .iter()
-        .map(|(name, ty)| {
-            arg_to_consume_line_token_stream(
-                name,
-                ty,
-                &syn::Ident::new("lines", fn_sig.name.span()),
-            )
-        })
-        .collect::<Vec<_>>();
+fn consume_lines_from_row_num(
+    fn_sig: &FunctionSignature,
+    n: usize,
+    strict: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let lines_ident = syn::Ident::new("lines", fn_sig.name().span());
+    let result = consume_args(fn_sig.args(), &lines_ident, strict, n == 1)?;
     let n_lit = proc_macro2::Literal::usize_unsuffixed(n);
-    quote! {
-        let mut input = String::new();
-        for _ in 0..#n_lit {
-            std::io::stdin().read_line(&mut input).unwrap();
-        }
-        let mut lines = Lines::new(&input);
+    Ok(quote! {
+        let mut lines = Lines::from_reader(std::io::stdin().lock());
+        lines.limit_lines(#n_lit);
         #(#result)*
-    }
+    })
 }
+// `row = n` is the expression `n`, which may also be bound inside a pattern like `(n, m)`
 fn consume_lines_from_var_name(
     fn_sig: &FunctionSignature,
-    var_name: &str,
-) -> proc_macro2::TokenStream {
-    let result = fn_sig
-        .args()
-        .iter()
-        .map(|(name, ty)| {
-            if name.to_string() == var_name {
-                return quote! {
-                   let #name = lines.consume::<usize>().unwrap();
-                   let mut input = String::new();
-                   for _ in 0..#name {
-                       std::io::stdin().read_line(&mut input).unwrap();
-                   }
-                   lines.extend(&input);
-                };
-            }
-            arg_to_consume_line_token_stream(
-                name,
-                ty,
-                &syn::Ident::new("lines", fn_sig.name.span()),
-            )
-        })
-        .collect::<Vec<_>>();
-
-    quote! {
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
-        let mut lines = Lines::new(&input);
+    var_name: &Ident,
+    strict: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    consume_lines_from_expr(fn_sig, &syn::parse_quote!(#var_name), strict)
+}
 
+// the limit is set once every argument used by `expr` is read
+fn consume_lines_from_expr(
+    fn_sig: &FunctionSignature,
+    expr: &syn::Expr,
+    strict: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let vars = expr_vars(expr)
+        .into_iter()
+        .map(|var| Ok((var, row_var(fn_sig, var)?)))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let last = vars.iter().max_by_key(|(_, (position, _))| *position);
+    if let Some((var, (last, _))) = last {
+        if fn_sig.args()[last + 1..].iter().all(Arg::is_skipped) {
+            return Err(syn::Error::new(
+                var.span(),
+                format!(
+                    "`{}` must be declared before the arguments it gives the rows of",
+                    var
+                ),
+            ));
+        }
+    }
+    let last = last.map(|(_, (last, _))| *last);
+    let checked = checked_expr(expr);
+    let all_usize = vars.iter().all(|(_, (_, ty))| is_usize(ty));
+    let limit = if all_usize && checked.is_none() {
+        quote! {
+            lines.limit_lines(#expr);
+        }
+    } else {
+        // an overflow or a negative number is reported with the values it was computed from
+        let rows = Ident::new("rows", proc_macro2::Span::mixed_site());
+        let value = match checked {
+            Some(checked) if all_usize => checked,
+            Some(checked) => quote! { #checked.and_then(|#rows| usize::try_from(#rows).ok()) },
+            None => quote! { usize::try_from(#expr).ok() },
+        };
+        let message = format!("`{}` is not a number of rows", expr.to_token_stream());
+        let found = vars
+            .iter()
+            .map(|(var, _)| format!("{} = {{}}", var))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let vars = vars.iter().map(|(var, _)| var);
+        quote! {
+            let #rows = #value.unwrap_or_else(|| {
+                lines.fail(#message, lines.invalid_value::<usize>(&format!(#found, #(#vars),*)))
+            });
+            lines.limit_lines(#rows);
+        }
+    };
+    let lines_ident = syn::Ident::new("lines", fn_sig.name().span());
+    let mut result = Vec::new();
+    if last.is_none() {
+        result.push(limit.clone());
+    }
+    for (i, arg) in fn_sig.args().iter().enumerate() {
+        result.push(arg_to_consume_line_token_stream(
+            arg,
+            &lines_ident,
+            strict,
+            false,
+        )?);
+        if Some(i) == last {
+            result.push(limit.clone());
+        }
+    }
+    Ok(quote! {
+        let mut lines = Lines::from_reader(std::io::stdin().lock());
         #(#result)*
+    })
+}
+// `expr` as an `Option` with `+`, `-`, `*`, `/` and `%` checked, like
+// `CheckedInt::checked_sub(n, 1)`; None when `expr` has none of them
+fn checked_expr(expr: &syn::Expr) -> Option<proc_macro2::TokenStream> {
+    struct Checked(usize);
+    impl VisitMut for Checked {
+        fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+            syn::visit_mut::visit_expr_mut(self, expr);
+            let syn::Expr::Binary(binary) = expr else {
+                return;
+            };
+            let method = match binary.op {
+                syn::BinOp::Add(_) => "checked_add",
+                syn::BinOp::Sub(_) => "checked_sub",
+                syn::BinOp::Mul(_) => "checked_mul",
+                syn::BinOp::Div(_) => "checked_div",
+                syn::BinOp::Rem(_) => "checked_rem",
+                _ => return,
+            };
+            let method = Ident::new(method, binary.op.span());
+            // the operands become arguments, so `(h - 1) * w` needs no parentheses
+            let unparen = |mut expr: &syn::Expr| {
+                while let syn::Expr::Paren(paren) = expr {
+                    expr = &paren.expr;
+                }
+                expr.clone()
+            };
+            let (left, right) = (unparen(&binary.left), unparen(&binary.right));
+            *expr = syn::parse_quote! {
+                pte::__private::CheckedInt::#method(#left, #right)?
+            };
+            self.0 += 1;
+        }
+    }
+    let mut expr = expr.clone();
+    let mut checked = Checked(0);
+    checked.visit_expr_mut(&mut expr);
+    if checked.0 == 0 {
+        return None;
+    }
+    while let syn::Expr::Paren(paren) = expr {
+        expr = *paren.expr;
+    }
+    // the outermost operation gives the `Option` itself, the others need a closure for `?`
+    let (value, inner) = match expr {
+        syn::Expr::Try(outer) => (outer.expr.into_token_stream(), checked.0 - 1),
+        expr => (quote! { Some(#expr) }, checked.0),
+    };
+    Some(if inner == 0 {
+        value
+    } else {
+        quote! { (|| #value)() }
+    })
+}
+// the variables used by `expr`, like `n` and `m` of `n * m.max(1)`
+fn expr_vars(expr: &syn::Expr) -> Vec<&Ident> {
+    struct Vars<'ast>(Vec<&'ast Ident>);
+    impl<'ast> Visit<'ast> for Vars<'ast> {
+        fn visit_expr_path(&mut self, path: &'ast syn::ExprPath) {
+            if let (None, Some(ident)) = (&path.qself, path.path.get_ident()) {
+                if !self.0.contains(&ident) {
+                    self.0.push(ident);
+                }
+            }
+        }
+    }
+    let mut vars = Vars(Vec::new());
+    vars.visit_expr(expr);
+    vars.0
+}
+// the position of the argument that binds `var`, and the integer type it is bound to
+fn row_var<'a>(fn_sig: &'a FunctionSignature, var: &Ident) -> syn::Result<(usize, &'a Type)> {
+    let args = fn_sig.args();
+    let Some(position) = args.iter().position(|arg| arg.bound_idents().contains(var)) else {
+        return Err(syn::Error::new(
+            var.span(),
+            format!("`{}` is not an argument of `{}`", var, fn_sig.name()),
+        ));
+    };
+    let arg = &args[position];
+    match arg.bound_type(var) {
+        Some(ty) if !arg.is_skipped() && is_integer(ty) => Ok((position, ty)),
+        ty => Err(syn::Error::new_spanned(
+            ty.unwrap_or(arg.owned_ty()),
+            format!(
+                "`{}` gives the number of rows, so it must be read as an integer like `usize`",
+                var
+            ),
+        )),
+    }
+}
+// reads `args` in order, starting at the start of a line; a Vec is a line of its own,
+// unless the input is `one_line` and the Vec takes what the arguments before it left
+fn consume_args(
+    args: &[Arg],
+    lines_ident: &Ident,
+    strict: bool,
+    one_line: bool,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut started = false;
+    let mut result = Vec::new();
+    for arg in args {
+        result.push(arg_to_consume_line_token_stream(
+            arg,
+            lines_ident,
+            strict,
+            started,
+        )?);
+        if one_line && !arg.is_skipped() {
+            started = !arg.reads_lines()?;
+        }
     }
+    Ok(result)
+}
+fn idents(tokens: proc_macro2::TokenStream) -> Vec<Ident> {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => vec![ident],
+            proc_macro2::TokenTree::Group(group) => idents(group.stream()),
+            _ => Vec::new(),
+        })
+        .collect()
 }
 
+// `started` is set when an earlier argument read tokens from the line at the cursor,
+// so that a line read by `arg` is the rest of that line
 fn arg_to_consume_line_token_stream(
-    name: &Ident,
+    arg: &Arg,
+    lines_ident: &Ident,
+    strict: bool,
+    started: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = arg.binding();
+    let ty = arg.owned_ty();
+    if arg.is_skipped() {
+        let output = marker_output(ty);
+        return Ok(quote! {
+            let #name = <#output>::default();
+        });
+    }
+    let unwrap = unwrap_or_report(&arg.display_name(), ty, lines_ident);
+    let read = read_arg(arg, name, arg.read_ty(), &unwrap, lines_ident, started)?;
+    let Some(rows) = arg.rows() else {
+        return Ok(read);
+    };
+    // what is left of the rows is skipped, or an error in strict mode
+    let outer = Ident::new("outer", proc_macro2::Span::mixed_site());
+    let leave = if strict {
+        quote! { #lines_ident.finish_lines(#outer).#unwrap; }
+    } else {
+        quote! { #lines_ident.leave_lines(#outer); }
+    };
+    Ok(quote! {
+        let #outer = #lines_ident.enter_lines(#rows);
+        #read
+        #leave
+    })
+}
+// `let #name = ..;` reading `ty` with the layout attributes of `arg`
+fn read_arg(
+    arg: &Arg,
+    name: &proc_macro2::TokenStream,
     ty: &Type,
+    unwrap: &proc_macro2::TokenStream,
     lines_ident: &Ident,
-) -> proc_macro2::TokenStream {
+    started: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some(rows) = arg.columns() {
+        let elems = column_types(ty)?;
+        return Ok(quote! {
+            let #name = #lines_ident.try_consume_columns::<(#(#elems,)*)>(#rows).#unwrap;
+        });
+    }
+    // other collections are read like a Vec of their elements and collected
+    if let Some(elem) = collection_elem(ty) {
+        let values = quote! { values };
+        let read = read_arg(
+            arg,
+            &values,
+            &syn::parse_quote!(Vec<#elem>),
+            unwrap,
+            lines_ident,
+            started,
+        )?;
+        let output = marker_output(ty);
+        return Ok(quote! {
+            let #name = {
+                #read
+                values.into_iter().collect::<#output>()
+            };
+        });
+    }
     if is_vec(ty) {
-        let ty = get_vec_type(ty).unwrap();
-        if is_vec(ty) {
-            let ty = get_vec_type(ty).unwrap();
-            return quote! {
-                let #name = #lines_ident.consume_to_two_d_vec::<#ty>().unwrap();
+        let elem = get_vec_type(ty)?;
+        // all tokens of the rows are elements
+        let rows_of_tokens = arg.rows().is_some() && !is_vec(elem);
+        if reads_records(elem) || rows_of_tokens {
+            let read = read_tokens(elem, &quote! { &mut #lines_ident }, &quote! { .#unwrap });
+            let Some(len) = arg.len() else {
+                // read until the input runs out
+                return Ok(quote! {
+                    let #name = {
+                        let mut values = Vec::new();
+                        while #lines_ident.has_next_data() {
+                            values.push(#read);
+                        }
+                        values
+                    };
+                });
             };
+            return Ok(quote! {
+                let #name = (0..#len).map(|_| #read).collect::<Vec<_>>();
+            });
+        }
+        // `T` of `Vec<Vec<T>>`
+        let inner = is_vec(elem).then(|| get_vec_type(elem)).transpose()?;
+        if let Some(inner) = inner.filter(|inner| is_index(inner)) {
+            let read_line = read_line_with(inner, lines_ident);
+            let Some(len) = arg.len() else {
+                return Ok(quote! {
+                    let #name = {
+                        let mut values = Vec::new();
+                        while #lines_ident.has_next_data() {
+                            values.push(#read_line.#unwrap);
+                        }
+                        values
+                    };
+                });
+            };
+            return Ok(quote! {
+                let #name = (0..#len)
+                    .map(|_| #read_line)
+                    .collect::<Result<Vec<_>, _>>()
+                    .#unwrap;
+            });
+        }
+        if let Some(ty) = inner {
+            let Some(len) = arg.len() else {
+                return Ok(quote! {
+                    let #name = #lines_ident.try_consume_to_two_d_vec::<#ty>().#unwrap;
+                });
+            };
+            // one row per line
+            return Ok(quote! {
+                let #name = #lines_ident.try_consume_rows::<#ty>(#len).#unwrap;
+            });
+        }
+        if is_index(elem) {
+            let read = read_tokens(elem, &quote! { &mut #lines_ident }, &quote! { .#unwrap });
+            let Some(len) = arg.len() else {
+                let read_line = if started {
+                    quote! { #lines_ident.try_consume_rest_of_line_with(<#elem as Readable>::read) }
+                } else {
+                    quote! { #lines_ident.try_consume_next_line_with(<#elem as Readable>::read) }
+                };
+                return Ok(quote! {
+                    let #name = #read_line.#unwrap;
+                });
+            };
+            return Ok(quote! {
+                let #name = (0..#len).map(|_| #read).collect::<Vec<_>>();
+            });
+        }
+        if let Some(len) = arg.len() {
+            return Ok(quote! {
+                let #name = #lines_ident.try_consume_n::<#elem>(#len).#unwrap;
+            });
+        }
+        // a line of its own, or the rest of the line the arguments before it are on
+        if started {
+            return Ok(quote! {
+                let #name = #lines_ident.try_consume_rest_of_line::<#elem>().#unwrap;
+            });
+        }
+        return Ok(quote! {
+            let #name = #lines_ident.try_consume_next_line::<#elem>().#unwrap;
+        });
+    }
+    let read = read_tokens(ty, &quote! { &mut #lines_ident }, &quote! { .#unwrap });
+    Ok(quote! {
+        let #name = #read;
+    })
+}
+// element types of `(Vec<A>, Vec<B>, ..)` read by `#[columns]`
+fn column_types(ty: &Type) -> syn::Result<Vec<&Type>> {
+    let Type::Tuple(tuple) = ty else {
+        return Err(syn::Error::new(
+            ty.span(),
+            "`#[columns]` expects a tuple of Vec like `(Vec<usize>, Vec<usize>)`",
+        ));
+    };
+    tuple
+        .elems
+        .iter()
+        .map(|ty| {
+            if !is_vec(ty) {
+                return Err(syn::Error::new(ty.span(), "`#[columns]` expects a Vec"));
+            }
+            get_vec_type(ty)
+        })
+        .collect()
+}
+// types that cannot be read are reported at the type, not in the generated code;
+// `in_record` is set inside tuples, arrays, `Option` and derived fields, which are read
+// token by token, so no collection can be there
+fn check_type(ty: &Type, in_record: bool) -> syn::Result<()> {
+    let is_collection = is_vec(ty) || collection_elem(ty).is_some();
+    if in_record && is_collection {
+        let mut message = format!(
+            "`{}` is read from whole lines, so it cannot be inside a tuple, an array or an `Option`",
+            ty.to_token_stream()
+        );
+        if let Some(marker) = token_marker(ty) {
+            message += &format!(
+                "; read one token as `{}` with `{}`, or with `#[{}]` on a derived field",
+                type_display(ty),
+                marker,
+                marker_attr(&syn::parse_quote!(#marker))
+            );
+        }
+        return Err(syn::Error::new_spanned(ty, message));
+    }
+    match ty {
+        Type::Path(_) => generic_type(ty).map_or(Ok(()), |(_, args)| {
+            let in_record = in_record || option_type(ty).is_some();
+            args.into_iter()
+                .try_for_each(|ty| check_type(ty, in_record))
+        }),
+        Type::Tuple(tuple) => tuple.elems.iter().try_for_each(|ty| check_type(ty, true)),
+        Type::Array(array) => check_type(&array.elem, true),
+        Type::Paren(paren) => check_type(&paren.elem, in_record),
+        Type::Group(group) => check_type(&group.elem, in_record),
+        Type::Reference(_) => Err(syn::Error::new_spanned(
+            ty,
+            "only a whole argument can be borrowed, like `&str` or `&[T]`",
+        )),
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            format!("`{}` cannot be read from the input", ty.to_token_stream()),
+        )),
+    }
+}
+// the pattern bound in `main` without `mut`, and the expression passing on what it bound;
+// `_` is bound to a fresh name from `wild` so that it can be passed on too
+fn split_pat(
+    pat: &syn::Pat,
+    wild: &mut impl FnMut() -> Ident,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let unsupported = |pat: &dyn ToTokens, message: &str| {
+        Err(syn::Error::new_spanned(
+            pat,
+            format!("{} is not supported in the arguments of #[pte]", message),
+        ))
+    };
+    match pat {
+        syn::Pat::Ident(pat) if pat.by_ref.is_some() || pat.subpat.is_some() => {
+            unsupported(pat, "binding by `ref` or `@`")
+        }
+        syn::Pat::Ident(pat) => {
+            let ident = &pat.ident;
+            Ok((quote! { #ident }, quote! { #ident }))
+        }
+        syn::Pat::Wild(_) => {
+            let ident = wild();
+            Ok((quote! { #ident }, quote! { #ident }))
         }
+        syn::Pat::Tuple(tuple) => {
+            let (bindings, values) = split_pats(&tuple.elems, wild)?;
+            Ok((quote! { (#(#bindings,)*) }, quote! { (#(#values,)*) }))
+        }
+        syn::Pat::Slice(slice) => {
+            let (bindings, values) = split_pats(&slice.elems, wild)?;
+            Ok((quote! { [#(#bindings),*] }, quote! { [#(#values),*] }))
+        }
+        syn::Pat::TupleStruct(pat) => {
+            let path = &pat.path;
+            let (bindings, values) = split_pats(&pat.pat.elems, wild)?;
+            Ok((
+                quote! { #path(#(#bindings),*) },
+                quote! { #path(#(#values),*) },
+            ))
+        }
+        syn::Pat::Struct(pat) if pat.dot2_token.is_some() => unsupported(pat, "`..`"),
+        syn::Pat::Struct(pat) => {
+            let path = &pat.path;
+            let members = pat.fields.iter().map(|field| &field.member);
+            let (bindings, values) = split_pats(pat.fields.iter().map(|field| &*field.pat), wild)?;
+            let members = members.collect::<Vec<_>>();
+            Ok((
+                quote! { #path { #(#members: #bindings),* } },
+                quote! { #path { #(#members: #values),* } },
+            ))
+        }
+        _ => unsupported(pat, "this pattern"),
+    }
+}
+#[allow(clippy::type_complexity)]
+fn split_pats<'a>(
+    pats: impl IntoIterator<Item = &'a syn::Pat>,
+    wild: &mut impl FnMut() -> Ident,
+) -> syn::Result<(Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>)> {
+    let mut bindings = Vec::new();
+    let mut values = Vec::new();
+    for pat in pats {
+        let (binding, value) = split_pat(pat, wild)?;
+        bindings.push(binding);
+        values.push(value);
+    }
+    Ok((bindings, values))
+}
+// borrowed parameters are read as an owned value and lent:
+// `&str` as `String`, `&[T]` as `Vec<T>` and `&T` as `T`
+// (the owned type, whether it is lent mutably)
+fn owned_type(ty: &Type) -> Option<(Type, bool)> {
+    let Type::Reference(reference) = ty else {
+        return None;
+    };
+    let owned = match &*reference.elem {
+        Type::Path(path) if path.path.is_ident("str") => syn::parse_quote!(String),
+        Type::Slice(slice) => {
+            let elem = &slice.elem;
+            syn::parse_quote!(Vec<#elem>)
+        }
+        elem => elem.clone(),
+    };
+    Some((owned, reference.mutability.is_some()))
+}
+// expression reading `ty` from `lines: &mut Lines`, `finish` handles the Result of each read
+fn read_tokens(
+    ty: &Type,
+    lines: &proc_macro2::TokenStream,
+    finish: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if let Type::Tuple(tuple) = ty {
+        let elems = tuple.elems.iter().map(|ty| read_tokens(ty, lines, finish));
+        return quote! {
+            (#(#elems,)*)
+        };
+    }
+    // a loop rather than `[(); N].map(..)`, so that `finish` may return from the function
+    if let Type::Array(array) = ty {
+        let elem = read_tokens(&array.elem, lines, finish);
+        let len = &array.len;
+        return quote! {
+            {
+                let mut values = Vec::with_capacity(#len);
+                for _ in 0..#len {
+                    values.push(#elem);
+                }
+                <[_; #len]>::try_from(values).unwrap_or_else(|_| unreachable!())
+            }
+        };
+    }
+    // None when the current line has no more tokens
+    if let Some(elem) = option_type(ty) {
+        let elem = read_tokens(elem, lines, finish);
+        return quote! {
+            if Lines::has_next_in_line(#lines) { Some(#elem) } else { None }
+        };
+    }
+    if is_known(ty) {
         return quote! {
-            let #name = #lines_ident.consume_to_vec::<#ty>().unwrap();
+            <#ty as Readable>::read(#lines)#finish
         };
     }
+    // `Readable` if the type has it, or else `FromStr`
+    quote! {
+        (&Reader::<#ty>::NEW).read_value(#lines)#finish
+    }
+}
+// expression reading the rest of a line of `Usize1`/`Isize1`
+fn read_line_with(elem: &Type, lines_ident: &Ident) -> proc_macro2::TokenStream {
+    quote! {
+        #lines_ident.try_consume_to_vec_with(<#elem as Readable>::read)
+    }
+}
+// panic with the argument name and the position of the input
+// the report with the input around the error is built by `Lines::fail`, off the happy path
+fn unwrap_or_report(name: &str, ty: &Type, lines_ident: &Ident) -> proc_macro2::TokenStream {
+    let message = format!("failed to read `{}` as `{}`", name, type_display(ty));
     quote! {
-        let #name = #lines_ident.consume::<#ty>().unwrap();
+        unwrap_or_else(|e| #lines_ident.fail(#message, e))
     }
 }
+// `Vec<(usize, i64)>` rather than the spaced tokens `Vec < (usize , i64) >`
+fn type_display(ty: &Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(' ', "")
+        .replace(',', ", ")
+        .replace(';', "; ")
+}
 fn get_vec_type(ty: &Type) -> syn::Result<&Type> {
     let Type::Path(path) = ty else {
         return Err(syn::Error::new(ty.span(), "expected path"));
     };
-    let Some(segment) = path.path.segments.first() else {
+    let Some(segment) = path.path.segments.last() else {
         return Err(syn::Error::new(ty.span(), "expected segment"));
     };
     let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
         return Err(syn::Error::new(ty.span(), "expected angle bracketed"));
     };
-    let syn::GenericArgument::Type(ty) = args.args.first().unwrap() else {
+    let Some(syn::GenericArgument::Type(ty)) = args.args.first() else {
         return Err(syn::Error::new(ty.span(), "expected type"));
     };
     Ok(ty)
 }
 
-fn is_vec(ty: &Type) -> bool {
-    if let Type::Path(path) = ty {
-        if let Some(segment) = path.path.segments.first() {
-            if segment.ident == "Vec" {
-                return true;
-            }
-        }
-    }
-    false
+fn is_usize(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident("usize"))
 }
 
-struct FunctionSignature {
-    name: Ident,
-    args: Vec<(Ident, Type)>,
-    return_type: proc_macro2::TokenStream,
-    body: syn::Block,
+fn is_integer(ty: &Type) -> bool {
+    const INTEGER_TYPES: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    match ty {
+        Type::Path(path) => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| INTEGER_TYPES.iter().any(|name| ident == name)),
+        _ => false,
+    }
 }
 
-impl FunctionSignature {
-    fn name(&self) -> &Ident {
-        &self.name
-    }
-    fn args(&self) -> &[(Ident, Type)] {
-        &self.args
-    }
-    fn block(&self) -> &syn::Block {
-        &self.body
-    }
-    fn return_type(&self) -> &proc_macro2::TokenStream {
-        &self.return_type
+const TOKEN_TYPES: [&str; 17] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64", "bool", "char", "String",
+];
+
+// types read as one record each rather than as the tokens of a line: tuples, arrays,
+// `Chars`/`Bytes` and `Readable` types like derived structs
+fn reads_records(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(_) | Type::Array(_) => true,
+        Type::Path(_) => !is_vec(ty) && !is_index(ty) && !is_token(ty),
+        _ => false,
     }
 }
 
-impl Parse for FunctionSignature {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let _fn: syn::Token![fn] = input.parse()?;
-        let name: Ident = input.parse()?;
-        let content;
-        let _parentheses = syn::parenthesized!(content in input);
-        let args = content
-            .parse_terminated::<_, syn::Token![,]>(|input| {
-                let name: Ident = input.parse()?;
-                let _colon: syn::Token![:] = input.parse()?;
-                let ty: Type = input.parse()?;
-                Ok((name, ty))
-            })?
-            .into_iter()
-            .collect();
-
-        let return_type = if input.peek(syn::Token![->]) {
-            let _arrow: syn::Token![->] = input.parse()?;
-            let return_type: Type = input.parse()?;
-            quote! { -> #return_type }
-        } else {
-            quote! {}
-        };
-        let body: syn::Block = input.parse().map_err(|e| {
-            syn::Error::new(
-                e.span(),
-                format!(
-                    "expected block expression for function body {}",
-                    input.to_string()
-                ),
-            )
-        })?;
+fn is_token(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .is_some_and(|segment| TOKEN_TYPES.iter().any(|name| segment.ident == name))
+}
 
-        Ok(Self {
-            name,
-            args,
-            return_type,
-            body,
-        })
-    }
+// types with a `Readable` impl of pte, the rest may only have `FromStr`
+fn is_known(ty: &Type) -> bool {
+    is_token(ty) || marker_ident(ty).is_some()
 }
 
-fn fn_parse(input: ParseStream) -> syn::Result<FunctionSignature> {
-    FunctionSignature::parse(input)
+// the markers read as another type: `Usize1`/`Isize1`, 1-based in the input and 0-based in
+// the function, and `Chars`/`Bytes`, one token read as its characters or bytes
+fn marker_ident(ty: &Type) -> Option<&Ident> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    MARKER_ATTRS
+        .iter()
+        .any(|(_, name)| segment.ident == name)
+        .then_some(&segment.ident)
 }
 
-#[derive(Debug)]
-struct PteAttrParser<'a> {
-    attr: &'a str,
+fn is_index(ty: &Type) -> bool {
+    marker_ident(ty).is_some_and(|ident| ident == "Usize1" || ident == "Isize1")
 }
 
-impl PteAttrParser<'_> {
-    const ROW_KEY: &'static str = "row";
-    fn new(attr: &str) -> PteAttrParser {
-        PteAttrParser { attr }
-    }
-    fn exist_row_num_at_input(&self) -> bool {
-        if !self.attr.contains(Self::ROW_KEY) {
-            return false;
-        }
-        let row_value = self.get_row_attr_value();
-        row_value.get(0..2).is_some_and(|s| s == "in")
-    }
-    fn exist_row_num(&self) -> bool {
-        self.get_row_num().is_ok()
+// what a marker is read as
+fn marker_type(marker: &Ident) -> Type {
+    let span = marker.span();
+    match marker.to_string().as_str() {
+        "Usize1" => syn::parse_quote_spanned!(span=> usize),
+        "Isize1" => syn::parse_quote_spanned!(span=> isize),
+        "Chars" => syn::parse_quote_spanned!(span=> Vec<char>),
+        _ => syn::parse_quote_spanned!(span=> Vec<u8>),
     }
+}
 
-    fn exist_row_num_at_var_name(&self) -> bool {
-        if !self.attr.contains(Self::ROW_KEY) {
-            return false;
-        }
-        !(self.exist_row_num_at_input() || self.exist_row_num())
-    }
-    fn get_var_name(&self) -> Result<&str, String> {
-        if !self.exist_row_num_at_var_name() {
-            return Err(format!("row number var name not found in {}", self.attr));
+// `ty` with each marker replaced by what it is read as, like `Vec<usize>` for `Vec<Usize1>`
+fn marker_output(ty: &Type) -> Type {
+    struct ReplaceMarkers;
+    impl VisitMut for ReplaceMarkers {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            match marker_ident(ty) {
+                Some(ident) => *ty = marker_type(ident),
+                None => syn::visit_mut::visit_type_mut(self, ty),
+            }
         }
-        let row_value = self.get_row_attr_value();
-        Ok(row_value)
     }
-    // get by default or row = NUMBER
-    fn get_row_num(&self) -> Result<isize, String> {
-        if self.attr == "" || !self.attr.contains(Self::ROW_KEY) {
-            return Ok(self.default_row_num());
+    let mut ty = ty.clone();
+    ReplaceMarkers.visit_type_mut(&mut ty);
+    ty
+}
+
+// the first marker in `ty`
+fn find_marker(ty: &Type) -> Option<&Type> {
+    struct FindMarker<'ast>(Option<&'ast Type>);
+    impl<'ast> Visit<'ast> for FindMarker<'ast> {
+        fn visit_type(&mut self, ty: &'ast Type) {
+            if self.0.is_some() {
+                return;
+            }
+            if marker_ident(ty).is_some() {
+                self.0 = Some(ty);
+            } else {
+                syn::visit::visit_type(self, ty);
+            }
         }
-        let row_value = self.get_row_attr_value();
-        row_value.parse::<isize>().map_err(|e| e.to_string())
     }
+    let mut finder = FindMarker(None);
+    finder.visit_type(ty);
+    finder.0
+}
 
-    fn get_input_ref(&self) -> Result<usize, String> {
-        if self.attr == "" || !self.attr.contains(Self::ROW_KEY) {
-            return Err("input reference not found".to_string());
-        }
-        self.parse_input_ref()
+// the name and type arguments of the last segment, like `VecDeque` and `[T]` of
+// `std::collections::VecDeque<T>`
+fn generic_type(ty: &Type) -> Option<(&Ident, Vec<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some((&segment.ident, args))
+}
+
+// `T` of `Option<T>`
+fn option_type(ty: &Type) -> Option<&Type> {
+    match generic_type(ty)? {
+        (ident, args) if ident == "Option" && args.len() == 1 => Some(args[0]),
+        _ => None,
     }
+}
 
-    fn parse_input_ref(&self) -> Result<usize, String> {
-        fn error_msg(v: &str) -> String {
-            format!("invalid input reference {}, format is \"inNUMBER\"", v)
-        }
-        assert!(self.exist_row_num_at_input());
-        let row_value = self.get_row_attr_value();
-        let Ok(result) = row_value[2..3].parse::<usize>() else {
-            return Err(error_msg(row_value));
-        };
-        Ok(result)
+// the element of a collection other than Vec: `T` of `BTreeSet<T>`, `(K, V)` of `HashMap<K, V>`
+fn collection_elem(ty: &Type) -> Option<Type> {
+    const SETS: [&str; 4] = ["VecDeque", "HashSet", "BTreeSet", "BinaryHeap"];
+    const MAPS: [&str; 2] = ["HashMap", "BTreeMap"];
+    let (ident, args) = generic_type(ty)?;
+    if SETS.iter().any(|name| ident == name) && args.len() == 1 {
+        return Some(args[0].clone());
     }
-    fn get_row_attr_value(&self) -> &str {
-        if self.attr == "" || !self.attr.contains(Self::ROW_KEY) {
-            return "";
+    if MAPS.iter().any(|name| ident == name) && args.len() >= 2 {
+        let (key, value) = (args[0], args[1]);
+        return Some(syn::parse_quote!((#key, #value)));
+    }
+    None
+}
+
+fn is_vec(ty: &Type) -> bool {
+    generic_type(ty).is_some_and(|(ident, args)| ident == "Vec" && args.len() == 1)
+}
+
+// `Chars` for `Vec<char>` and `Bytes` for `Vec<u8>`, the markers reading them from one token
+fn token_marker(ty: &Type) -> Option<Ident> {
+    ["Chars", "Bytes"]
+        .into_iter()
+        .map(|name| Ident::new(name, ty.span()))
+        .find(|marker| {
+            marker_type(marker).to_token_stream().to_string() == ty.to_token_stream().to_string()
+        })
+}
+
+// `#[chars]` or `#[bytes]` on an argument: each `Vec<char>` or `Vec<u8>` in `ty` is one token
+// like `#..#`, read with the marker `Chars` or `Bytes`
+fn whole_token_type(ty: &Type, attr: &syn::Attribute, marker: &Ident) -> syn::Result<Type> {
+    struct Mark<'a>(&'a Ident, bool);
+    impl VisitMut for Mark<'_> {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            if token_marker(ty).is_some_and(|marker| marker == *self.0) {
+                let marker = self.0;
+                *ty = syn::parse_quote_spanned!(marker.span()=> pte::#marker);
+                self.1 = true;
+            } else {
+                syn::visit_mut::visit_type_mut(self, ty);
+            }
         }
-        let mut attrs = self.attr.split(",");
-        let row_attr = attrs
-            .find(|attr| attr.contains(Self::ROW_KEY))
-            .unwrap_or_default();
-        let split = row_attr.split("=");
-        split.last().unwrap_or_default().trim()
     }
-    fn default_row_num(&self) -> isize {
-        1
+    let mut mark = Mark(marker, false);
+    let mut ty = ty.clone();
+    mark.visit_type_mut(&mut ty);
+    if !mark.1 {
+        return Err(syn::Error::new_spanned(
+            attr,
+            format!(
+                "`#[{}]` expects a `{}` or a Vec of them",
+                marker_attr(&syn::parse_quote!(#marker)),
+                type_display(&marker_type(marker))
+            ),
+        ));
     }
+    Ok(ty)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+struct Arg {
+    pat: syn::Pat,
+    len: Option<syn::Expr>,
+    rows: Option<syn::Expr>,
+    columns: Option<syn::Expr>,
+    skip: bool,
+    // pattern bound in `main`, and the value passed from there to the function
+    binding: proc_macro2::TokenStream,
+    value: proc_macro2::TokenStream,
+    owned_ty: Type,
+    // `owned_ty` with `#[chars]` or `#[bytes]` applied, like `Vec<Chars>` for `Vec<Vec<char>>`
+    read_ty: Type,
+}
+
+impl Arg {
+    const WHOLE_TOKEN_ATTRS: [&'static str; 2] = ["chars", "bytes"];
+    const LEN_ATTR: &'static str = "len";
+    const ROWS_ATTR: &'static str = "rows";
+    const COLUMNS_ATTR: &'static str = "columns";
+    const SKIP_ATTR: &'static str = "skip";
+    // `index` is the position of the argument, to name what `_` binds
+    fn new(index: usize, attrs: Vec<syn::Attribute>, pat: syn::Pat, ty: Type) -> syn::Result<Self> {
+        let len = Self::parse_count(&attrs, Self::LEN_ATTR)?;
+        let rows = Self::parse_count(&attrs, Self::ROWS_ATTR)?;
+        let columns = Self::parse_columns(&attrs)?;
+        let skip = attrs.iter().any(|attr| attr.path.is_ident(Self::SKIP_ATTR));
+        let mut wilds = 0usize;
+        let (mut binding, mut value) = split_pat(&pat, &mut || {
+            wilds += 1;
+            quote::format_ident!("__pte_arg{}_{}", index, wilds)
+        })?;
+        let owned_ty = match owned_type(&ty) {
+            Some((owned_ty, true)) => {
+                // a temporary is lent for other patterns
+                if matches!(pat, syn::Pat::Ident(_)) {
+                    binding = quote! { mut #binding };
+                }
+                value = quote! { &mut #value };
+                owned_ty
+            }
+            Some((owned_ty, false)) => {
+                value = quote! { &#value };
+                owned_ty
+            }
+            None => ty.clone(),
+        };
+        let whole_token = MARKER_ATTRS
+            .iter()
+            .filter(|(name, _)| Self::WHOLE_TOKEN_ATTRS.contains(name))
+            .find_map(|(name, marker)| {
+                let attr = attrs.iter().find(|attr| attr.path.is_ident(name))?;
+                Some((attr, Ident::new(marker, attr.span())))
+            });
+        let read_ty = match whole_token {
+            Some((attr, marker)) => whole_token_type(&owned_ty, attr, &marker)?,
+            None => owned_ty.clone(),
+        };
+        // the columns are read as records of their elements
+        match &columns {
+            Some(_) => column_types(&read_ty)?
+                .into_iter()
+                .try_for_each(|ty| check_type(ty, true))?,
+            None => check_type(&read_ty, false)?,
+        }
+        if let Some(len) = &len {
+            if !is_vec(&owned_ty) && collection_elem(&owned_ty).is_none() {
+                return Err(syn::Error::new_spanned(
+                    len,
+                    "`#[len]` expects a Vec or another collection",
+                ));
+            }
+        }
+        Ok(Arg {
+            pat,
+            len,
+            rows,
+            columns,
+            skip,
+            binding,
+            value,
+            owned_ty,
+            read_ty,
+        })
+    }
+    // `#[name = expr]`
+    fn parse_count(attrs: &[syn::Attribute], name: &str) -> syn::Result<Option<syn::Expr>> {
+        let Some(attr) = attrs.iter().find(|attr| attr.path.is_ident(name)) else {
+            return Ok(None);
+        };
+        let len = (|input: ParseStream| {
+            let _eq: syn::Token![=] = input.parse()?;
+            input.parse::<syn::Expr>()
+        })
+        .parse2(attr.tokens.clone())?;
+        Ok(Some(len))
+    }
+    fn parse_columns(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::Expr>> {
+        attrs
+            .iter()
+            .find(|attr| attr.path.is_ident(Self::COLUMNS_ATTR))
+            .map(|attr| attr.parse_args::<syn::Expr>())
+            .transpose()
+    }
+    // the name of a plain `name: Type` argument
+    fn ident(&self) -> Option<&Ident> {
+        match &self.pat {
+            syn::Pat::Ident(pat) => Some(&pat.ident),
+            _ => None,
+        }
+    }
+    // the names bound by the pattern, available to later arguments
+    fn bound_idents(&self) -> Vec<Ident> {
+        idents(self.binding.clone())
+    }
+    // the type that the pattern binds `ident` to, if it can be told from the signature
+    fn bound_type(&self, ident: &Ident) -> Option<&Type> {
+        fn find<'a>(pat: &syn::Pat, ty: &'a Type, ident: &Ident) -> Option<&'a Type> {
+            match (pat, ty) {
+                (_, Type::Paren(ty)) => find(pat, &ty.elem, ident),
+                (syn::Pat::Ident(pat), _) => (pat.ident == *ident).then_some(ty),
+                (syn::Pat::Tuple(pat), Type::Tuple(ty)) => pat
+                    .elems
+                    .iter()
+                    .zip(&ty.elems)
+                    .find_map(|(pat, ty)| find(pat, ty, ident)),
+                (syn::Pat::Slice(pat), Type::Array(ty)) => {
+                    pat.elems.iter().find_map(|pat| find(pat, &ty.elem, ident))
+                }
+                _ => None,
+            }
+        }
+        find(&self.pat, &self.owned_ty, ident)
+    }
+    fn display_name(&self) -> String {
+        match self.ident() {
+            Some(ident) => ident.to_string(),
+            None => self.pat.to_token_stream().to_string().replace(" ,", ","),
+        }
+    }
+    fn binding(&self) -> &proc_macro2::TokenStream {
+        &self.binding
+    }
+    fn value(&self) -> proc_macro2::TokenStream {
+        self.value.clone()
+    }
+    // the type read from the input, which is lent when `ty` is a reference
+    fn owned_ty(&self) -> &Type {
+        &self.owned_ty
+    }
+    // the type the reads are generated for, with markers in place of `#[chars]`/`#[bytes]`
+    fn read_ty(&self) -> &Type {
+        &self.read_ty
+    }
+    // `#[len = n]`: the Vec has exactly n elements, whatever the line layout is
+    fn len(&self) -> Option<&syn::Expr> {
+        self.len.as_ref()
+    }
+    // `#[columns(n)]`: n rows are split into one Vec per column
+    fn columns(&self) -> Option<&syn::Expr> {
+        self.columns.as_ref()
+    }
+    // `#[rows = n]`: the argument is read from the next n lines
+    fn rows(&self) -> Option<&syn::Expr> {
+        self.rows.as_ref()
+    }
+    // `#[skip]`: the argument is not in the input and gets its default value
+    fn is_skipped(&self) -> bool {
+        self.skip
+    }
+    // whether the cursor is left at the start of a line, as after whole lines of a Vec
+    // rather than after tokens
+    fn reads_lines(&self) -> syn::Result<bool> {
+        if self.rows.is_some() || self.columns.is_some() {
+            return Ok(true);
+        }
+        let ty = self.read_ty();
+        let elem = match collection_elem(ty) {
+            Some(elem) => elem,
+            None if is_vec(ty) => get_vec_type(ty)?.clone(),
+            None => return Ok(false),
+        };
+        Ok(!reads_records(&elem) && (self.len.is_none() || is_vec(&elem)))
+    }
+    // the number of elements is given by the signature, not by the line layout
+    fn is_sized(&self) -> bool {
+        self.len.is_some() || self.rows.is_some() || self.columns.is_some()
+    }
+    // attributes for pte are removed from the declared function
+    fn is_pte_attr(attr: &syn::Attribute) -> bool {
+        [
+            Self::LEN_ATTR,
+            Self::ROWS_ATTR,
+            Self::COLUMNS_ATTR,
+            Self::SKIP_ATTR,
+        ]
+        .iter()
+        .any(|name| attr.path.is_ident(name))
+            || Self::WHOLE_TOKEN_ATTRS
+                .iter()
+                .any(|name| attr.path.is_ident(name))
+    }
+}
+
+struct FunctionSignature {
+    item: syn::ItemFn,
+    args: Vec<Arg>,
+}
+
+impl FunctionSignature {
+    fn name(&self) -> &Ident {
+        &self.item.sig.ident
+    }
+    fn args(&self) -> &[Arg] {
+        &self.args
+    }
+    fn item(&self) -> &syn::ItemFn {
+        &self.item
+    }
+}
+
+impl Parse for FunctionSignature {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let item: syn::ItemFn = input.parse()?;
+        let args = item
+            .sig
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| match input {
+                syn::FnArg::Typed(arg) => Arg::new(
+                    index,
+                    arg.attrs.clone(),
+                    (*arg.pat).clone(),
+                    (*arg.ty).clone(),
+                ),
+                syn::FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                    receiver,
+                    "#[pte] function cannot take `self`",
+                )),
+            })
+            .collect::<syn::Result<_>>()?;
+        Ok(Self { item, args })
+    }
+}
+
+fn fn_parse(input: ParseStream) -> syn::Result<FunctionSignature> {
+    FunctionSignature::parse(input)
+}
+
+// the arguments of `#[pte(..)]`
+#[derive(Default)]
+struct PteAttr {
+    row: Option<Row>,
+    strict: bool,
+}
+
+// `row = ..`: how many lines the input has
+enum Row {
+    // `row = 3`
+    Count(usize),
+    // `row = in1`: the number is the token at index 1 of the first line
+    Input(usize),
+    // `row = n`
+    Var(Ident),
+    // `row = n - 1`
+    Expr(Box<syn::Expr>),
+}
+
+impl PteAttr {
+    const ROW_KEY: &'static str = "row";
+    const STRICT_KEY: &'static str = "strict";
+    const DEFAULT_ROW_NUM: usize = 1;
+    fn row(&self) -> Option<&Row> {
+        self.row.as_ref()
+    }
+    // `strict`: leftover or missing input is an error instead of being ignored
+    fn is_strict(&self) -> bool {
+        self.strict
+    }
+}
+
+impl Parse for PteAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attr = PteAttr::default();
+        // `key = value`, or a flag without a value
+        let items = input.parse_terminated::<_, syn::Token![,]>(|input| {
+            let key: Ident = input.parse()?;
+            let value = match input.parse::<Option<syn::Token![=]>>()? {
+                Some(_) => Some(input.parse::<syn::Expr>()?),
+                None => None,
+            };
+            Ok((key, value))
+        })?;
+        for (key, value) in items {
+            let duplicate = if key == PteAttr::ROW_KEY {
+                let Some(value) = value else {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("expected `{} = ..`", key),
+                    ));
+                };
+                attr.row.replace(Row::parse(value)?).is_some()
+            } else if key == PteAttr::STRICT_KEY {
+                if let Some(value) = value {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        format!("`{}` takes no value", key),
+                    ));
+                }
+                std::mem::replace(&mut attr.strict, true)
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "unknown key `{}`, expected `{}` or `{}`",
+                        key,
+                        PteAttr::ROW_KEY,
+                        PteAttr::STRICT_KEY
+                    ),
+                ));
+            };
+            if duplicate {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("duplicate key `{}`", key),
+                ));
+            }
+        }
+        Ok(attr)
+    }
+}
+
+impl Row {
+    fn parse(value: syn::Expr) -> syn::Result<Self> {
+        match value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            }) => Ok(Row::Count(lit.base10_parse()?)),
+            syn::Expr::Lit(lit) => Err(syn::Error::new_spanned(
+                lit,
+                "expected the number of rows, `inK` or an argument",
+            )),
+            syn::Expr::Path(path) => match path.path.get_ident() {
+                Some(ident) => {
+                    let name = ident.to_string();
+                    match name.strip_prefix("in").map(str::parse::<usize>) {
+                        Some(Ok(input_ref)) => Ok(Row::Input(input_ref)),
+                        _ => Ok(Row::Var(ident.clone())),
+                    }
+                }
+                None => Ok(Row::Expr(Box::new(syn::Expr::Path(path)))),
+            },
+            expr => Ok(Row::Expr(Box::new(expr))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // statements reading the single argument of `fn solve(#arg) {}`
+    fn arg_statement(arg: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let fn_sig: FunctionSignature = syn::parse2(quote! { fn solve(#arg) {} }).unwrap();
+        let lines = syn::Ident::new("lines", proc_macro2::Span::call_site());
+        arg_to_consume_line_token_stream(&fn_sig.args()[0], &lines, false, false).unwrap()
+    }
     #[test]
     fn pte_test() {
         let attr = quote! { row = in1 };
@@ -406,24 +1528,24 @@ mod tests {
         let got = pte_impl(attr, item);
 
         let expect = quote! {
-            use pte::{
-                Lines,
-            };
             fn solve(v:usize) -> i32 {
             }
             fn main() {
-                let mut first_line = String::new();
-                std::io::stdin().read_line(&mut first_line).unwrap();
-
-                let row_num = first_line.split_whitespace().nth(1).unwrap().parse::<usize>().unwrap();
-
-                let mut input = String::new();
-                for _ in 0..row_num {
-                    std::io::stdin().read_line(&mut input).unwrap();
+                use pte::{
+                    Lines,
+                    Readable,
+                    __private::{ReadFromStr, ReadReadable, Reader},
+                };
+                let mut lines = Lines::from_reader(std::io::stdin().lock());
+                let outer = lines.enter_lines(1);
+                for _ in 0..1 {
+                    lines.next_data();
                 }
-                let mut lines = Lines::new(&input);
-                let v = lines.consume::<usize>().unwrap();
-                let result = solve(v);
+                let row_num = lines.try_consume::<usize>().unwrap_or_else(|e| lines.fail("failed to read the number of rows", e));
+                lines.leave_lines(outer);
+                lines.limit_lines(row_num);
+                let v = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `v` as `usize`", e));
+                let result = lines.run("solve", || solve(v));
                 println!("{}", result);
             }
         };
@@ -433,18 +1555,13 @@ mod tests {
     #[test]
     fn consume_line_statement_from_input() {
         let expect = quote! {
-            let mut first_line = String::new();
-            std::io::stdin().read_line(&mut first_line).unwrap();
-
-            let row_num = first_line.split_whitespace().nth(0).unwrap().parse::<usize>().unwrap();
-
-            let mut input = String::new();
-            for _ in 0..row_num {
-                std::io::stdin().read_line(&mut input).unwrap();
-            }
-            let mut lines = Lines::new(&input);
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
+            let outer = lines.enter_lines(1);
+            let row_num = lines.try_consume::<usize>().unwrap_or_else(|e| lines.fail("failed to read the number of rows", e));
+            lines.leave_lines(outer);
+            lines.limit_lines(row_num);
 
-            let v = lines.consume_to_vec::<usize>().unwrap();
+            let v = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `v` as `Vec<usize>`", e));
         };
         let fn_sig = syn::parse2(quote! {
             fn solve(v:Vec<usize>) -> usize {
@@ -452,19 +1569,33 @@ mod tests {
             }
         })
         .unwrap();
-        let got = consume_lines_from_input(&fn_sig, 0);
+        let got = consume_lines_from_input(&fn_sig, 0, false).unwrap();
         assert_eq!(got.to_string(), expect.to_string());
     }
 
     #[test]
-    fn consume_line_statement_from_row_num() {
+    fn strict_finishes_the_first_line() {
+        let fn_sig = syn::parse2(quote! { fn solve(v: Vec<usize>) {} }).unwrap();
+        let got = consume_lines_from_input(&fn_sig, 1, true).unwrap();
         let expect = quote! {
-            let mut input = String::new();
-            for _ in 0..3 {
-                std::io::stdin().read_line(&mut input).unwrap();
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
+            let outer = lines.enter_lines(1);
+            for _ in 0..1 {
+                lines.next_data();
             }
-            let mut lines = Lines::new(&input);
-            let vec = lines.consume_to_vec::<usize>().unwrap();
+            let row_num = lines.try_consume::<usize>().unwrap_or_else(|e| lines.fail("failed to read the number of rows", e));
+            lines.finish_lines(outer).unwrap_or_else(|e| lines.fail("the input does not match the arguments", e));
+            lines.limit_lines(row_num);
+        };
+        assert!(got.to_string().starts_with(&expect.to_string()));
+    }
+
+    #[test]
+    fn consume_line_statement_from_row_num() {
+        let expect = quote! {
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
+            lines.limit_lines(3);
+            let vec = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `vec` as `Vec<usize>`", e));
         };
         let fn_sig = quote! {
             fn solve(vec: Vec<usize>) -> usize {
@@ -473,27 +1604,20 @@ mod tests {
         };
         let fn_sig = syn::parse2(fn_sig).unwrap();
         let n = 3;
-        let got = consume_lines_from_row_num(&fn_sig, n);
+        let got = consume_lines_from_row_num(&fn_sig, n, false).unwrap();
         assert_eq!(got.to_string(), expect.to_string());
     }
 
     #[test]
     fn consume_line_statement_from_var_name() {
         let expect = quote! {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).unwrap();
-            let mut lines = Lines::new(&input);
-
-            let v = lines.consume::<usize>().unwrap();
-            let n = lines.consume::<usize>().unwrap();
-            let mut input = String::new();
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
 
-            for _ in 0..n {
-                std::io::stdin().read_line(&mut input).unwrap();
-            }
-            lines.extend(&input);
+            let v = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `v` as `usize`", e));
+            let n = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `n` as `usize`", e));
+            lines.limit_lines(n);
 
-            let vec = lines.consume_to_vec::<usize>().unwrap();
+            let vec = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `vec` as `Vec<usize>`", e));
         };
         let fn_sig = quote! {
             fn solve(v: usize,n: usize, vec: Vec<usize>) -> usize {
@@ -501,33 +1625,972 @@ mod tests {
             }
         };
         let fn_sig = syn::parse2(fn_sig).unwrap();
-        let got = consume_lines_from_var_name(&fn_sig, "n");
+        let n = syn::Ident::new("n", proc_macro2::Span::call_site());
+        let got = consume_lines_from_var_name(&fn_sig, &n, false).unwrap();
         assert_eq!(got.to_string(), expect.to_string());
     }
 
+    #[test]
+    fn row_var_uses_the_declared_type() {
+        let expect = quote! {
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
+
+            let n = <u32 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `n` as `u32`", e));
+            let rows = usize::try_from(n).ok().unwrap_or_else(|| {
+                lines.fail("`n` is not a number of rows", lines.invalid_value::<usize>(&format!("n = {}", n)))
+            });
+            lines.limit_lines(rows);
+
+            let vec = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `vec` as `Vec<usize>`", e));
+        };
+        let fn_sig = syn::parse2(quote! { fn solve(n: u32, vec: Vec<usize>) {} }).unwrap();
+        let n = syn::Ident::new("n", proc_macro2::Span::call_site());
+        let got = consume_lines_from_var_name(&fn_sig, &n, false).unwrap();
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+
+    #[test]
+    fn row_var_is_validated() {
+        let got = pte_impl(
+            quote! { row = n },
+            quote! { fn solve(n: f64, v: Vec<i64>) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`n` gives the number of rows, so it must be read as an integer"));
+
+        let got = pte_impl(
+            quote! { row = n },
+            quote! { fn solve(v: Vec<i64>, n: usize) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`n` must be declared before the arguments it gives the rows of"));
+
+        let got = pte_impl(
+            quote! { row = m },
+            quote! { fn solve(n: usize, v: Vec<i64>) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`m` is not an argument of `solve`"));
+
+        let got = pte_impl(
+            quote! { row = s },
+            quote! { fn solve((n, s): (usize, String), v: Vec<i64>) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`s` gives the number of rows, so it must be read as an integer"));
+    }
+
+    #[test]
+    fn row_var_may_be_bound_in_a_pattern() {
+        let expect = quote! {
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
+            let (n, m,) = (<usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `(n, m)` as `(usize, usize)`", e)), <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `(n, m)` as `(usize, usize)`", e)),);
+            lines.limit_lines(n);
+            let v = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `v` as `Vec<usize>`", e));
+        };
+        let fn_sig =
+            syn::parse2(quote! { fn solve((n, m): (usize, usize), v: Vec<usize>) {} }).unwrap();
+        let n = syn::Ident::new("n", proc_macro2::Span::call_site());
+        let got = consume_lines_from_var_name(&fn_sig, &n, false).unwrap();
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+
+    fn parse_row(attr: proc_macro2::TokenStream) -> Option<Row> {
+        syn::parse2::<PteAttr>(attr).unwrap().row
+    }
+    fn parse_error(attr: proc_macro2::TokenStream) -> String {
+        syn::parse2::<PteAttr>(attr).err().unwrap().to_string()
+    }
     #[test]
     fn parse_attr_row_from_input() {
-        let attr = "row = in0, column = in1";
-        let sut = PteAttrParser::new(attr);
-        assert!(sut.exist_row_num_at_input());
-        let got = sut.get_input_ref().unwrap();
-        assert_eq!(got, 0);
+        assert!(matches!(
+            parse_row(quote! { row = in0 }),
+            Some(Row::Input(0))
+        ));
+        assert!(matches!(
+            parse_row(quote! { row = in12 }),
+            Some(Row::Input(12))
+        ));
     }
     #[test]
     fn parse_attr_row_default() {
-        let attr = "";
-        let sut = PteAttrParser::new(attr);
-
-        assert!(!sut.exist_row_num_at_input());
-        let got = sut.get_row_num().unwrap();
-        assert_eq!(got, 1);
+        assert!(parse_row(quote! {}).is_none());
+    }
+    #[test]
+    fn parse_attr_row_from_num() {
+        assert!(matches!(parse_row(quote! { row = 3 }), Some(Row::Count(3))));
     }
     #[test]
     fn parse_attr_row_from_var_name() {
-        let attr = "row = n";
-        let sut = PteAttrParser::new(attr);
-        assert!(sut.exist_row_num_at_var_name());
-        let got = sut.get_var_name().unwrap();
-        assert_eq!(got, "n");
+        let Some(Row::Var(ident)) = parse_row(quote! { row = n }) else {
+            panic!("expected a variable");
+        };
+        assert_eq!(ident, "n");
+        // names that only look like `in` references are variables
+        assert!(matches!(
+            parse_row(quote! { row = input }),
+            Some(Row::Var(_))
+        ));
+        assert!(matches!(
+            parse_row(quote! { row = arrow }),
+            Some(Row::Var(_))
+        ));
+    }
+    #[test]
+    fn parse_attr_row_from_expr() {
+        let Some(Row::Expr(expr)) = parse_row(quote! { row = n - 1 }) else {
+            panic!("expected an expression");
+        };
+        assert_eq!(expr.to_token_stream().to_string(), "n - 1");
+    }
+    #[test]
+    fn parse_attr_errors() {
+        assert_eq!(
+            parse_error(quote! { rows = 3 }),
+            "unknown key `rows`, expected `row` or `strict`"
+        );
+        assert_eq!(
+            parse_error(quote! { row = 3, row = n }),
+            "duplicate key `row`"
+        );
+        assert_eq!(
+            parse_error(quote! { row = "3" }),
+            "expected the number of rows, `inK` or an argument"
+        );
+        assert_eq!(parse_error(quote! { row }), "expected `row = ..`");
+        assert_eq!(
+            parse_error(quote! { strict = true }),
+            "`strict` takes no value"
+        );
+        assert_eq!(
+            parse_error(quote! { strict, strict }),
+            "duplicate key `strict`"
+        );
+    }
+    #[test]
+    fn parse_attr_strict() {
+        let attr = syn::parse2::<PteAttr>(quote! { strict, row = 2 }).unwrap();
+        assert!(attr.is_strict());
+        assert!(matches!(attr.row(), Some(Row::Count(2))));
+        assert!(!syn::parse2::<PteAttr>(quote! {}).unwrap().is_strict());
+    }
+    #[test]
+    fn strict_finishes_the_input() {
+        let got = pte_impl(
+            quote! { strict },
+            quote! { fn solve(n: usize, #[rows = n] a: Vec<i64>) {} },
+        );
+        let expect = quote! {
+            fn solve(n: usize, a: Vec<i64>) {}
+            fn main() {
+                use pte::{
+                    Lines,
+                    Readable,
+                    __private::{ReadFromStr, ReadReadable, Reader},
+                };
+                let mut lines = Lines::from_reader(std::io::stdin().lock());
+
+                let n = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `n` as `usize`", e));
+                let outer = lines.enter_lines(n);
+                let a = {
+                    let mut values = Vec::new();
+                    while lines.has_next_data() {
+                        values.push(<i64 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<i64>`", e)));
+                    }
+                    values
+                };
+                lines.finish_lines(outer).unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<i64>`", e));
+                lines.finish().unwrap_or_else(|e| lines.fail("the input does not match the arguments", e));
+                let result = lines.run("solve", || solve(n, a));
+                println!("{}", result);
+            }
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_from_row_expr() {
+        let attr = quote! { row = n * m };
+        let item = quote! {
+            fn solve(n: usize, m: usize, a: Vec<Vec<u8>>) -> usize {}
+        };
+        let got = pte_impl(attr, item);
+        let expect = quote! {
+            fn solve(n: usize, m: usize, a: Vec<Vec<u8> >) -> usize {}
+            fn main() {
+                use pte::{
+                    Lines,
+                    Readable,
+                    __private::{ReadFromStr, ReadReadable, Reader},
+                };
+                let mut lines = Lines::from_reader(std::io::stdin().lock());
+                let n = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `n` as `usize`", e));
+                let m = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `m` as `usize`", e));
+                let rows = pte::__private::CheckedInt::checked_mul(n, m).unwrap_or_else(|| {
+                    lines.fail("`n * m` is not a number of rows", lines.invalid_value::<usize>(&format!("n = {}, m = {}", n, m)))
+                });
+                lines.limit_lines(rows);
+                let a = lines.try_consume_to_two_d_vec::<u8>().unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<Vec<u8>>`", e));
+                let result = lines.run("solve", || solve(n, m, a));
+                println!("{}", result);
+            }
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn row_expr_converts_signed_arguments() {
+        let fn_sig = syn::parse2(quote! {
+            fn solve(n: i64, (h, w): (usize, usize), a: Vec<Vec<u8>>) {}
+        })
+        .unwrap();
+        let expr: syn::Expr = syn::parse_quote!(n - 1);
+        let got = consume_lines_from_expr(&fn_sig, &expr, false).unwrap();
+        let expect = quote! {
+            let mut lines = Lines::from_reader(std::io::stdin().lock());
+            let n = <i64 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `n` as `i64`", e));
+            let rows = pte::__private::CheckedInt::checked_sub(n, 1)
+                .and_then(|rows| usize::try_from(rows).ok())
+                .unwrap_or_else(|| {
+                lines.fail("`n - 1` is not a number of rows", lines.invalid_value::<usize>(&format!("n = {}", n)))
+            });
+            lines.limit_lines(rows);
+        };
+        assert!(got.to_string().starts_with(&expect.to_string()));
+
+        // variables bound by a pattern count, and `usize` is not converted
+        let expr: syn::Expr = syn::parse_quote!(h * w);
+        let got = consume_lines_from_expr(&fn_sig, &expr, false).unwrap();
+        let expect = quote! {
+            let rows = pte::__private::CheckedInt::checked_mul(h, w).unwrap_or_else(|| {
+                lines.fail("`h * w` is not a number of rows", lines.invalid_value::<usize>(&format!("h = {}, w = {}", h, w)))
+            });
+        };
+        assert!(got.to_string().contains(&expect.to_string()));
+
+        // inner operations are checked with `?` in a closure
+        let expr: syn::Expr = syn::parse_quote!((h - 1) * w);
+        let got = consume_lines_from_expr(&fn_sig, &expr, false).unwrap();
+        let expect = quote! {
+            let rows = (|| pte::__private::CheckedInt::checked_mul(pte::__private::CheckedInt::checked_sub(h, 1)?, w))()
+        };
+        assert!(got.to_string().contains(&expect.to_string()));
+
+        // without arithmetic a `usize` needs no check
+        let expr: syn::Expr = syn::parse_quote!(h.max(w));
+        let got = consume_lines_from_expr(&fn_sig, &expr, false).unwrap();
+        assert!(got
+            .to_string()
+            .contains("lines . limit_lines (h . max (w)) ;"));
+    }
+    #[test]
+    fn row_expr_is_validated() {
+        let got = pte_impl(
+            quote! { row = k - 1 },
+            quote! { fn solve(n: usize, a: Vec<i64>) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`k` is not an argument of `solve`"));
+
+        let got = pte_impl(
+            quote! { row = n - 1 },
+            quote! { fn solve(n: f64, a: Vec<i64>) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`n` gives the number of rows, so it must be read as an integer"));
+
+        let got = pte_impl(
+            quote! { row = n * m },
+            quote! { fn solve(n: usize, a: Vec<i64>, m: usize) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`m` must be declared before the arguments it gives the rows of"));
+
+        // method names and paths are not variables
+        let got = pte_impl(
+            quote! { row = n.max(1) + usize::MIN },
+            quote! { fn solve(n: usize, a: Vec<i64>) {} },
+        );
+        assert!(!got.to_string().contains("compile_error"));
+    }
+    #[test]
+    fn consume_line_statement_tuple() {
+        let got = arg_statement(quote! { p: (usize, (char, i64)) });
+        let expect = quote! {
+            let p = (
+                <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `(usize, (char, i64))`", e)),
+                (
+                    <char as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `(usize, (char, i64))`", e)),
+                    <i64 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `(usize, (char, i64))`", e)),
+                ),
+            );
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_vec_of_tuple() {
+        let got = arg_statement(quote! { edges: Vec<(usize, usize)> });
+        let expect = quote! {
+            let edges = {
+                let mut values = Vec::new();
+                while lines.has_next_data() {
+                    values.push((
+                        <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `edges` as `Vec<(usize, usize)>`", e)),
+                        <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `edges` as `Vec<(usize, usize)>`", e)),
+                    ));
+                }
+                values
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_array() {
+        let got = arg_statement(quote! { grid: [[char; 3]; N] });
+        let expect = quote! {
+            let grid = {
+                let mut values = Vec::with_capacity(N);
+                for _ in 0..N {
+                    values.push({
+                        let mut values = Vec::with_capacity(3);
+                        for _ in 0..3 {
+                            values.push(<char as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `grid` as `[[char; 3]; N]`", e)));
+                        }
+                        <[_; 3]>::try_from(values).unwrap_or_else(|_| unreachable!())
+                    });
+                }
+                <[_; N]>::try_from(values).unwrap_or_else(|_| unreachable!())
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_chars() {
+        let got = arg_statement(quote! { s: Chars });
+        let expect = quote! {
+            let s = <Chars as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `s` as `Chars`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[chars] s: Vec<char> });
+        let expect = quote! {
+            let s = <pte::Chars as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `s` as `Vec<char>`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        // the function gets what the marker is read as
+        let fn_sig = syn::parse2(quote! { fn solve(s: Chars, t: Vec<(usize, Bytes)>) {} }).unwrap();
+        let expect = quote! { fn solve(s: Vec<char>, t: Vec<(usize, Vec<u8>)>) {} };
+        assert_eq!(fn_declare(&fn_sig).to_string(), expect.to_string());
+
+        let got = pte_impl(quote! {}, quote! { fn solve(#[chars] s: Vec<u8>) {} });
+        assert!(got
+            .to_string()
+            .contains("`#[chars]` expects a `Vec<char>` or a Vec of them"));
+    }
+    #[test]
+    fn consume_line_statement_grid() {
+        let expect = quote! {
+            let grid = {
+                let mut values = Vec::new();
+                while lines.has_next_data() {
+                    values.push(<Chars as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `grid` as `Vec<Chars>`", e)));
+                }
+                values
+            };
+        };
+        let got = arg_statement(quote! { grid: Vec<Chars> });
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let expect = quote! {
+            let grid = {
+                let mut values = Vec::new();
+                while lines.has_next_data() {
+                    values.push(<pte::Bytes as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `grid` as `Vec<Vec<u8>>`", e)));
+                }
+                values
+            };
+        };
+        let got = arg_statement(quote! { #[bytes] grid: Vec<Vec<u8>> });
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn declared_function_has_no_pte_attributes() {
+        let fn_sig = syn::parse2(quote! {
+            fn solve(#[chars] s: Vec<char>, #[allow(unused)] n: usize) {}
+        })
+        .unwrap();
+        let got = fn_declare(&fn_sig);
+        let expect = quote! {
+            fn solve(s: Vec<char>, #[allow(unused)] n: usize) {}
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_with_len() {
+        let got = arg_statement(quote! { #[len = n] a: Vec<i64> });
+        let expect = quote! {
+            let a = lines.try_consume_n::<i64>(n).unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<i64>`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[len = 3] p: Vec<(usize, char)> });
+        let expect = quote! {
+            let p = (0..3).map(|_| (
+                <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `Vec<(usize, char)>`", e)),
+                <char as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `Vec<(usize, char)>`", e)),
+            )).collect::<Vec<_>>();
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[len = h] rows: Vec<Vec<u8>> });
+        let expect = quote! {
+            let rows = lines.try_consume_rows::<u8>(h).unwrap_or_else(|e| lines.fail("failed to read `rows` as `Vec<Vec<u8>>`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn len_args_read_without_row_limit() {
+        let attr = quote! {};
+        let item = quote! {
+            fn solve(n: usize, #[len = n] a: Vec<i64>) {}
+        };
+        let got = pte_impl(attr, item);
+        let expect = quote! {
+            fn solve(n: usize, a: Vec<i64>) {}
+            fn main() {
+                use pte::{
+                    Lines,
+                    Readable,
+                    __private::{ReadFromStr, ReadReadable, Reader},
+                };
+                let mut lines = Lines::from_reader(std::io::stdin().lock());
+                let n = <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `n` as `usize`", e));
+                let a = lines.try_consume_n::<i64>(n).unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<i64>`", e));
+                let result = lines.run("solve", || solve(n, a));
+                println!("{}", result);
+            }
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_columns() {
+        let got = arg_statement(quote! { #[columns(n)] (a, b): (Vec<usize>, Vec<i64>) });
+        let expect = quote! {
+            let (a, b,) = lines.try_consume_columns::<(usize, i64,)>(n).unwrap_or_else(|e| lines.fail("failed to read `(a, b)` as `(Vec<usize>, Vec<i64>)`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn columns_are_passed_as_tuple() {
+        let fn_sig = syn::parse2(quote! {
+            fn solve(n: usize, #[columns(n)] (a, b): (Vec<usize>, Vec<usize>)) -> usize {}
+        })
+        .unwrap();
+        let expect = quote! {
+            solve(n, (a, b,))
+        };
+        assert_eq!(fn_execute(&fn_sig).to_string(), expect.to_string());
+        let expect = quote! {
+            fn solve(n: usize, (a, b): (Vec<usize>, Vec<usize>)) -> usize {}
+        };
+        assert_eq!(fn_declare(&fn_sig).to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_vec_of_readable() {
+        let got = arg_statement(quote! { #[len = m] edges: Vec<Edge> });
+        let expect = quote! {
+            let edges = (0..m).map(|_| (&Reader::<Edge>::NEW).read_value(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `edges` as `Vec<Edge>`", e))).collect::<Vec<_>>();
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn derive_readable_struct() {
+        let got = derive_readable_impl(quote! {
+            struct Edge {
+                u: usize,
+                v: usize,
+                w: i64,
+            }
+        });
+        let expect = quote! {
+            const _: () = {
+                use pte::{Lines, ParseError, Readable, __private::{ReadFromStr, ReadReadable, Reader}};
+                impl Readable for Edge {
+                    type Output = Self;
+                    fn read(lines: &mut Lines<'_>) -> Result<Self, ParseError> {
+                        Ok(Edge {
+                            u: <usize as Readable>::read(lines)?,
+                            v: <usize as Readable>::read(lines)?,
+                            w: <i64 as Readable>::read(lines)?
+                        })
+                    }
+                }
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = derive_readable_impl(quote! {
+            struct Pair<T>(T, (char, T));
+        });
+        let expect = quote! {
+            const _: () = {
+                use pte::{Lines, ParseError, Readable, __private::{ReadFromStr, ReadReadable, Reader}};
+                impl<T: Readable<Output = T> > Readable for Pair<T> {
+                    type Output = Self;
+                    fn read(lines: &mut Lines<'_>) -> Result<Self, ParseError> {
+                        Ok(Pair(
+                            (&Reader::<T>::NEW).read_value(lines)?,
+                            (<char as Readable>::read(lines)?, (&Reader::<T>::NEW).read_value(lines)?,)
+                        ))
+                    }
+                }
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn derive_readable_array_and_option() {
+        let got = derive_readable_impl(quote! {
+            struct Edge {
+                ends: [usize; 2],
+                label: Option<char>,
+            }
+        });
+        let expect = quote! {
+            const _: () = {
+                use pte::{Lines, ParseError, Readable, __private::{ReadFromStr, ReadReadable, Reader}};
+                impl Readable for Edge {
+                    type Output = Self;
+                    fn read(lines: &mut Lines<'_>) -> Result<Self, ParseError> {
+                        Ok(Edge {
+                            ends: {
+                                let mut values = Vec::with_capacity(2);
+                                for _ in 0..2 {
+                                    values.push(<usize as Readable>::read(lines)?);
+                                }
+                                <[_; 2]>::try_from(values).unwrap_or_else(|_| unreachable!())
+                            },
+                            label: if Lines::has_next_in_line(lines) {
+                                Some(<char as Readable>::read(lines)?)
+                            } else {
+                                None
+                            }
+                        })
+                    }
+                }
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn derive_readable_enum() {
+        let got = derive_readable_impl(quote! {
+            enum Query {
+                #[tag(1)]
+                Add(usize, i64),
+                #[tag(2)]
+                Rename { id: usize, name: String },
+                #[tag("print")]
+                Print,
+            }
+        });
+        let expect = quote! {
+            const _: () = {
+                use pte::{Lines, ParseError, Readable, __private::{ReadFromStr, ReadReadable, Reader}};
+                impl Readable for Query {
+                    type Output = Self;
+                    fn read(lines: &mut Lines<'_>) -> Result<Self, ParseError> {
+                        let tag = lines.try_consume_tag::<Self>(&["1", "2", "print"])?;
+                        Ok(match tag {
+                            0 => Query::Add(<usize as Readable>::read(lines)?, <i64 as Readable>::read(lines)?),
+                            1 => Query::Rename {
+                                id: <usize as Readable>::read(lines)?,
+                                name: <String as Readable>::read(lines)?
+                            },
+                            2 => Query::Print,
+                            _ => unreachable!(),
+                        })
+                    }
+                }
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = derive_readable_impl(quote! {
+            enum Query {
+                #[tag(1)]
+                Add(usize),
+                Print,
+            }
+        });
+        assert!(got.to_string().contains("each variant needs a tag"));
+        let got = derive_readable_impl(quote! {
+            enum Query {
+                #[tag(1)]
+                Add(usize),
+                #[tag(1)]
+                Print,
+            }
+        });
+        assert!(got.to_string().contains("duplicate tag `1`"));
+    }
+    #[test]
+    fn consume_line_statement_one_based() {
+        let got = arg_statement(quote! { s: Usize1 });
+        let expect = quote! {
+            let s = <Usize1 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `s` as `Usize1`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[len = m] edges: Vec<(Usize1, Isize1)> });
+        let expect = quote! {
+            let edges = (0..m).map(|_| (
+                <Usize1 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `edges` as `Vec<(Usize1, Isize1)>`", e)),
+                <Isize1 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `edges` as `Vec<(Usize1, Isize1)>`", e)),
+            )).collect::<Vec<_>>();
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { p: Vec<Usize1> });
+        let expect = quote! {
+            let p = lines.try_consume_next_line_with(<Usize1 as Readable>::read).unwrap_or_else(|e| lines.fail("failed to read `p` as `Vec<Usize1>`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[len = h] g: Vec<Vec<Usize1>> });
+        let expect = quote! {
+            let g = (0..h)
+                .map(|_| lines.try_consume_to_vec_with(<Usize1 as Readable>::read))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_else(|e| lines.fail("failed to read `g` as `Vec<Vec<Usize1>>`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn one_based_markers_are_declared_as_integers() {
+        let fn_sig = syn::parse2(quote! {
+            fn solve(s: Usize1, #[columns(n)] (a, b): (Vec<Usize1>, Vec<i64>), p: Option<[pte::Isize1; 2]>) {}
+        })
+        .unwrap();
+        let expect = quote! {
+            fn solve(s: usize, (a, b): (Vec<usize>, Vec<i64>), p: Option<[isize; 2]>) {}
+        };
+        assert_eq!(fn_declare(&fn_sig).to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[columns(n)] (a, b): (Vec<Usize1>, Vec<i64>) });
+        let expect = quote! {
+            let (a, b,) = lines.try_consume_columns::<(Usize1, i64,)>(n).unwrap_or_else(|e| lines.fail("failed to read `(a, b)` as `(Vec<Usize1>, Vec<i64>)`", e));
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[skip] seen: BTreeSet<Usize1> });
+        let expect = quote! {
+            let seen = <BTreeSet<usize> >::default();
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn derive_readable_one_based() {
+        let got = derive_readable_impl(quote! {
+            struct Edge(#[one_based] usize, #[one_based] isize);
+        });
+        assert!(got.to_string().contains(
+            "Edge (< pte :: Usize1 as Readable > :: read (lines) ? , < pte :: Isize1 as Readable > :: read (lines) ?)"
+        ));
+        let got = derive_readable_impl(quote! { struct Edge(Usize1, (Isize1, char)); });
+        assert!(got.to_string().contains(
+            "`Usize1` holds no value, so declare the field as `usize` with `#[one_based]`"
+        ));
+        let got = derive_readable_impl(quote! { struct Edge(#[one_based] u32); });
+        assert!(got
+            .to_string()
+            .contains("`#[one_based]` expects a `usize` or `isize` field"));
+    }
+    #[test]
+    fn derive_readable_whole_token() {
+        let got = derive_readable_impl(quote! {
+            struct Row { #[chars] cells: Vec<char>, #[bytes] name: Vec<u8> }
+        });
+        assert!(got.to_string().contains(
+            "Row { cells : < pte :: Chars as Readable > :: read (lines) ? , name : < pte :: Bytes as Readable > :: read (lines) ? }"
+        ));
+        let got = derive_readable_impl(quote! { struct Row(Chars); });
+        assert!(got.to_string().contains(
+            "`Chars` holds no value, so declare the field as `Vec<char>` with `#[chars]`"
+        ));
+        let got = derive_readable_impl(quote! { struct Row(Vec<u8>); });
+        assert!(got
+            .to_string()
+            .contains("read one token as `Vec<u8>` with `Bytes`, or with `#[bytes]`"));
+        let got = derive_readable_impl(quote! { struct Row(#[bytes] Vec<char>); });
+        assert!(got
+            .to_string()
+            .contains("`#[bytes]` expects a `Vec<u8>` field"));
+    }
+    #[test]
+    fn consume_line_statement_option() {
+        let got = arg_statement(quote! { k: Option<i64> });
+        let expect = quote! {
+            let k = if Lines::has_next_in_line(&mut lines) {
+                Some(<i64 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `k` as `Option<i64>`", e)))
+            } else {
+                None
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { p: Option<(Usize1, char)> });
+        let expect = quote! {
+            let p = if Lines::has_next_in_line(&mut lines) {
+                Some((
+                    <Usize1 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `Option<(Usize1, char)>`", e)),
+                    <char as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `p` as `Option<(Usize1, char)>`", e)),
+                ))
+            } else {
+                None
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_collections() {
+        let got = arg_statement(quote! { q: std::collections::VecDeque<usize> });
+        let expect = quote! {
+            let q = {
+                let values = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `q` as `std::collections::VecDeque<usize>`", e));
+                values.into_iter().collect::<std::collections::VecDeque<usize> >()
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[len = n] scores: BTreeMap<String, i64> });
+        let expect = quote! {
+            let scores = {
+                let values = (0..n).map(|_| (
+                    <String as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `scores` as `BTreeMap<String, i64>`", e)),
+                    <i64 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `scores` as `BTreeMap<String, i64>`", e)),
+                )).collect::<Vec<_>>();
+                values.into_iter().collect::<BTreeMap<String, i64> >()
+            };
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn vec_is_detected_by_last_segment() {
+        let ty: Type = syn::parse_quote!(std::vec::Vec<u8>);
+        assert!(is_vec(&ty));
+        let ty: Type = syn::parse_quote!(std::collections::VecDeque<u8>);
+        assert!(!is_vec(&ty));
+        assert!(collection_elem(&ty).is_some());
+    }
+    #[test]
+    fn line_reads_follow_the_signature() {
+        let reads = |attr, item| {
+            pte_impl(attr, item)
+                .to_string()
+                .split_whitespace()
+                .filter(|word| {
+                    word.starts_with("try_consume_rest_of_line")
+                        || word.starts_with("try_consume_next_line")
+                })
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        // on a single line, `a` is the rest of the line `k` is on and `p` is the line after it
+        let item =
+            quote! { fn solve(k: usize, #[skip] memo: Vec<usize>, a: Vec<i64>, p: Vec<Usize1>) {} };
+        let got = reads(quote! {}, item.clone());
+        assert_eq!(
+            got,
+            ["try_consume_rest_of_line", "try_consume_next_line_with"]
+        );
+        // on more lines, every Vec is a line of its own
+        let got = reads(quote! { row = 3 }, item);
+        assert_eq!(got, ["try_consume_next_line", "try_consume_next_line_with"]);
+        let got = reads(
+            quote! { row = n },
+            quote! { fn solve(n: usize, a: Vec<i64>, b: Vec<i64>) {} },
+        );
+        assert_eq!(got, ["try_consume_next_line", "try_consume_next_line"]);
+    }
+    #[test]
+    fn patterns_and_borrowed_args() {
+        let attr = quote! {};
+        let item = quote! {
+            fn solve(mut a: Vec<usize>, (h, _): (usize, char), s: &str, b: &mut [i64], [x, y]: [u8; 2]) {}
+        };
+        let got = pte_impl(attr, item);
+        let expect = quote! {
+            fn solve(mut a: Vec<usize>, (h, _): (usize, char), s: &str, b: &mut [i64], [x, y]: [u8; 2]) {}
+            fn main() {
+                use pte::{
+                    Lines,
+                    Readable,
+                    __private::{ReadFromStr, ReadReadable, Reader},
+                };
+                let mut lines = Lines::from_reader(std::io::stdin().lock());
+                lines.limit_lines(1);
+                let a = lines.try_consume_next_line::<usize>().unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<usize>`", e));
+                let (h, __pte_arg1_1,) = (
+                    <usize as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `(h, _)` as `(usize, char)`", e)),
+                    <char as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `(h, _)` as `(usize, char)`", e)),
+                );
+                let s = <String as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `s` as `String`", e));
+                let mut b = lines.try_consume_rest_of_line::<i64>().unwrap_or_else(|e| lines.fail("failed to read `b` as `Vec<i64>`", e));
+                let [x, y] = {
+                    let mut values = Vec::with_capacity(2);
+                    for _ in 0..2 {
+                        values.push(<u8 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `[x, y]` as `[u8; 2]`", e)));
+                    }
+                    <[_; 2]>::try_from(values).unwrap_or_else(|_| unreachable!())
+                };
+                let result = lines.run("solve", || solve(a, (h, __pte_arg1_1,), &s, &mut b, [x, y]));
+                println!("{}", result);
+            }
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn declared_function_is_kept() {
+        let fn_sig = syn::parse2(quote! {
+            /// Sums up `a`.
+            #[inline]
+            #[allow(clippy::ptr_arg)]
+            pub fn solve<T>(#[len = 3] a: Vec<T>, #[allow(unused)] n: usize) -> T
+            where
+                T: std::iter::Sum<T>,
+            {
+                a.into_iter().sum()
+            }
+        })
+        .unwrap();
+        let expect = quote! {
+            /// Sums up `a`.
+            #[inline]
+            #[allow(clippy::ptr_arg)]
+            pub fn solve<T>(a: Vec<T>, #[allow(unused)] n: usize) -> T
+            where
+                T: std::iter::Sum<T>,
+            {
+                a.into_iter().sum()
+            }
+        };
+        assert_eq!(fn_declare(&fn_sig).to_string(), expect.to_string());
+    }
+    #[test]
+    fn consume_line_statement_rows_and_skip() {
+        let got = arg_statement(quote! { #[rows = m] a: Vec<i64> });
+        let expect = quote! {
+            let outer = lines.enter_lines(m);
+            let a = {
+                let mut values = Vec::new();
+                while lines.has_next_data() {
+                    values.push(<i64 as Readable>::read(&mut lines).unwrap_or_else(|e| lines.fail("failed to read `a` as `Vec<i64>`", e)));
+                }
+                values
+            };
+            lines.leave_lines(outer);
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[rows = h] grid: Vec<Vec<u8>> });
+        let expect = quote! {
+            let outer = lines.enter_lines(h);
+            let grid = lines.try_consume_to_two_d_vec::<u8>().unwrap_or_else(|e| lines.fail("failed to read `grid` as `Vec<Vec<u8>>`", e));
+            lines.leave_lines(outer);
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = arg_statement(quote! { #[skip] memo: Vec<usize> });
+        let expect = quote! {
+            let memo = <Vec<usize> >::default();
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+    }
+    #[test]
+    fn skipped_args_do_not_change_the_layout() {
+        let read = |item| {
+            let fn_sig = syn::parse2(item).unwrap();
+            consume_lines(&fn_sig, &PteAttr::default())
+                .unwrap()
+                .to_string()
+        };
+        let got = read(quote! {
+            fn solve(a: usize, #[skip] memo: Vec<usize>, vv: Vec<(usize, usize)>) {}
+        });
+        let expect = read(quote! { fn solve(a: usize, vv: Vec<(usize, usize)>) {} });
+        let memo = quote! { let memo = <Vec<usize> >::default(); }.to_string();
+        assert!(expect.contains("lines . limit_lines (1) ;"));
+        assert_eq!(got.replace(&format!("{} ", memo), ""), expect);
+    }
+    #[test]
+    fn errors_are_reported_at_compile_time() {
+        let got = pte_impl(quote! {}, quote! { struct Solve; });
+        let expect = quote! {
+            struct Solve;
+
+            fn main() {
+                compile_error! { "expected `fn`" }
+            }
+        };
+        assert_eq!(got.to_string(), expect.to_string());
+
+        let got = pte_impl(quote! { row = x }, quote! { fn solve(n: usize) {} });
+        assert!(got
+            .to_string()
+            .contains("`x` is not an argument of `solve`"));
+
+        let got = pte_impl(quote! {}, quote! { fn solve(f: impl Fn()) {} });
+        assert!(got
+            .to_string()
+            .contains("`impl Fn ()` cannot be read from the input"));
+
+        let got = pte_impl(quote! {}, quote! { fn solve(p: (&str, usize)) {} });
+        assert!(got
+            .to_string()
+            .contains("only a whole argument can be borrowed"));
+
+        let got = pte_impl(quote! {}, quote! { fn solve(#[len = 3] n: usize) {} });
+        assert!(got
+            .to_string()
+            .contains("`#[len]` expects a Vec or another collection"));
+
+        // collections are read from whole lines, not as one field of a record
+        let got = pte_impl(quote! {}, quote! { fn solve(p: (usize, Vec<usize>)) {} });
+        assert!(got
+            .to_string()
+            .contains("`Vec < usize >` is read from whole lines"));
+        let got = pte_impl(
+            quote! {},
+            quote! { fn solve(a: Option<BTreeSet<usize>>) {} },
+        );
+        assert!(got
+            .to_string()
+            .contains("`BTreeSet < usize >` is read from whole lines"));
+        let got = pte_impl(
+            quote! {},
+            quote! { fn solve(p: (usize, Chars), s: Option<Bytes>) {} },
+        );
+        assert!(!got.to_string().contains("compile_error !"));
+        // a `Vec<char>` in a record is a line too, one token needs the marker
+        let got = pte_impl(quote! {}, quote! { fn solve(p: (usize, Vec<char>)) {} });
+        assert!(got.to_string().contains(
+            "; read one token as `Vec<char>` with `Chars`, or with `#[chars]` on a derived field"
+        ));
+
+        // the function is kept without the attributes of pte, so only the error is reported
+        let got = pte_impl(quote! {}, quote! { fn solve(#[len = 3] n: usize) {} });
+        let got = got.to_string();
+        assert!(got.starts_with(&quote! { fn solve(n: usize) {} fn main() }.to_string()));
+        assert_eq!(got.matches("compile_error !").count(), 1);
+
+        let got = derive_readable_impl(quote! { struct P(*const u8); });
+        assert!(got.to_string().contains("cannot be read from the input"));
     }
 }
@@ -0,0 +1,14 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 1 2 3
+// # . #
+// . # .
+// # . #
+#[pte(row = 4)]
+fn solve(p: [usize; 3], grid: [[char; 3]; 3]) -> usize {
+    let walls = grid.iter().flatten().filter(|&&c| c == '#').count();
+    p.iter().sum::<usize>() * walls
+}
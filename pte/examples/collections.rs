@@ -0,0 +1,17 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3 1 3 2 3
+// 2
+// alice 10
+// bob 20
+#[pte(row = 4)]
+fn solve(queue: VecDeque<usize>, n: usize, #[len = n] scores: BTreeMap<String, i64>) -> String {
+    assert_eq!(scores.len(), n);
+    let set = queue.iter().collect::<BTreeSet<_>>();
+    format!("{} {} {}", queue.front().unwrap(), set.len(), scores["bob"])
+}
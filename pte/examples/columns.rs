@@ -0,0 +1,14 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3
+// 1 10
+// 2 20
+// 3 30
+#[pte]
+fn solve(n: usize, #[columns(n)] (a, b): (Vec<usize>, Vec<usize>)) -> usize {
+    assert_eq!(a.len(), n);
+    a.iter().sum::<usize>() * b.iter().sum::<usize>()
+}
@@ -2,7 +2,6 @@ use pte::d_pte;
 
 d_pte!(
     fn solve(a: isize, b: isize) -> isize {
-        let a = a + b;
-        a
+        a + b
     }
 );
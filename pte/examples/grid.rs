@@ -0,0 +1,15 @@
+use pte::{pte, Chars};
+
+// ex:
+// ```shell
+// cargo run
+// 3 4
+// #..#
+// .##.
+// #...
+#[pte(row = h)]
+fn solve(h: usize, w: usize, grid: Vec<Chars>) -> usize {
+    assert_eq!(grid.len(), h);
+    assert!(grid.iter().all(|row| row.len() == w));
+    grid.iter().flatten().filter(|&&c| c == '#').count()
+}
@@ -0,0 +1,14 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3
+// #..#
+// .##.
+// #...
+#[pte(row = h)]
+fn solve(h: usize, #[chars] grid: Vec<Vec<char>>) -> usize {
+    assert_eq!(grid.len(), h);
+    grid.iter().flatten().filter(|&&c| c == '#').count()
+}
@@ -0,0 +1,36 @@
+use pte::{pte, Readable, Usize1};
+
+#[derive(Readable)]
+struct Query {
+    #[one_based]
+    v: usize,
+    k: i64,
+}
+
+// ex:
+// ```shell
+// cargo run
+// 4 3
+// 1 2
+// 2 3
+// 4 1
+// 3 1 2 4
+// 4 10
+#[pte]
+fn solve(
+    n: usize,
+    m: usize,
+    #[len = m] edges: Vec<(Usize1, Usize1)>,
+    #[len = n] order: Vec<Usize1>,
+    query: Query,
+) -> i64 {
+    let mut degree = vec![0; n];
+    for &(u, v) in &edges {
+        degree[u] += 1;
+        degree[v] += 1;
+    }
+    assert_eq!(edges.len(), m);
+    assert_eq!(query.v, 3);
+    // degree of the first vertex in the order
+    degree[order[0]] * query.k
+}
@@ -0,0 +1,13 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 10 3
+// or
+// cargo run
+// 10
+#[pte]
+fn solve(n: usize, k: Option<usize>) -> usize {
+    n * k.unwrap_or(1)
+}
@@ -0,0 +1,13 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 2 3
+// 5 1 4
+// abc
+#[pte(row = 3)]
+fn solve((h, w): (usize, usize), mut a: Vec<i64>, s: &str) -> String {
+    a.sort();
+    format!("{} {:?} {}", h * w, a, s.len())
+}
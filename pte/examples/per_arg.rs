@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3 2
+// 10
+// 20
+// 30
+// 1 2
+// 2 3
+#[pte]
+fn solve(
+    n: usize,
+    m: usize,
+    #[rows = n] a: Vec<i64>,
+    #[rows = m] edges: Vec<(usize, usize)>,
+    #[skip] mut memo: HashMap<usize, i64>,
+) -> i64 {
+    for &(u, v) in &edges {
+        memo.insert(v, a[u - 1] + a[v - 1]);
+    }
+    assert_eq!(a.len(), n);
+    assert_eq!(edges.len(), m);
+    memo.values().sum()
+}
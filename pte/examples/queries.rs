@@ -0,0 +1,34 @@
+use pte::{pte, Readable};
+
+#[derive(Readable)]
+enum Query {
+    #[tag(1)]
+    Add(usize, i64),
+    #[tag(2)]
+    Remove(usize),
+    #[tag(3)]
+    Print,
+}
+
+// ex:
+// ```shell
+// cargo run
+// 3 4
+// 1 0 5
+// 1 2 7
+// 2 0
+// 3
+#[pte]
+fn solve(n: usize, q: usize, #[len = q] queries: Vec<Query>) -> String {
+    assert_eq!(queries.len(), q);
+    let mut a = vec![0; n];
+    let mut out = Vec::new();
+    for query in queries {
+        match query {
+            Query::Add(i, x) => a[i] += x,
+            Query::Remove(i) => a[i] = 0,
+            Query::Print => out.push(a.iter().sum::<i64>().to_string()),
+        }
+    }
+    out.join("\n")
+}
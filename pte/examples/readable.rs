@@ -0,0 +1,23 @@
+use pte::{pte, Readable};
+
+#[derive(Readable)]
+struct Edge {
+    ends: [usize; 2],
+    w: i64,
+    label: Option<char>,
+}
+
+// ex:
+// ```shell
+// cargo run
+// 3 2
+// 1 2 10 a
+// 2 3 -4
+#[pte]
+fn solve(n: usize, m: usize, #[len = m] edges: Vec<Edge>) -> i64 {
+    assert_eq!(edges.len(), m);
+    assert!(edges.iter().all(|e| e.ends.iter().all(|&v| v <= n)));
+    assert_eq!(edges[0].label, Some('a'));
+    assert_eq!(edges[1].label, None);
+    edges.iter().map(|e| e.w).sum()
+}
@@ -0,0 +1,14 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 4
+// 1 2
+// 1 3
+// 3 4
+#[pte(row = n - 1)]
+fn solve(n: usize, edges: Vec<(usize, usize)>) -> usize {
+    assert_eq!(edges.len(), n - 1);
+    edges.iter().map(|&(u, v)| u * v).sum()
+}
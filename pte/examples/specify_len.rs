@@ -0,0 +1,16 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3
+// 1
+// 2
+// 3
+// 2
+// 10 20
+#[pte]
+fn solve(n: usize, #[len = n] a: Vec<i64>, m: usize, #[len = m] b: Vec<i64>) -> i64 {
+    assert_eq!((a.len(), b.len()), (n, m));
+    a.iter().sum::<i64>() * b.iter().sum::<i64>()
+}
@@ -3,7 +3,7 @@ use pte::pte;
 // ex:
 // ```shell
 // cargo run
-// 4
+// 3 4
 // 1 2
 // 3 4 5
 // 6 7
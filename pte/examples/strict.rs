@@ -0,0 +1,13 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3
+// 10 20 30
+// or with `10 20 30 40`, the token left is an error instead of being ignored
+#[pte(strict)]
+fn solve(n: usize, #[len = n] a: Vec<i64>) -> i64 {
+    assert_eq!(a.len(), n);
+    a.iter().sum()
+}
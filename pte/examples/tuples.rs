@@ -0,0 +1,13 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3 2
+// 1 2 10
+// 2 3 -4
+#[pte(row = m)]
+fn solve(n: usize, m: usize, edges: Vec<(usize, usize, i64)>) -> i64 {
+    assert_eq!(edges.len(), m);
+    edges.iter().map(|&(u, v, w)| (u + v + n) as i64 * w).sum()
+}
@@ -0,0 +1,22 @@
+use pte::pte;
+
+// ex:
+// ```shell
+// cargo run
+// 3
+// 1 2 3
+/// Average of the values, kept as a normal function that tests can call.
+#[pte(row = 2)]
+#[inline]
+pub fn solve(n: usize, #[len = n] a: &[i64]) -> i64 {
+    a.iter().sum::<i64>() / n as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn average() {
+        assert_eq!(solve(3, &[1, 2, 3]), 2);
+    }
+}
@@ -1,5 +1,13 @@
-pub use helper::Lines;
-pub use r#macro::pte;
+pub use helper::{
+    Bytes, Chars, Checkpoint, Columns, Isize1, LineLimit, Lines, ParseError, Readable, Usize1,
+};
+pub use r#macro::{pte, Readable};
+
+// used by the code that `#[pte]` and `#[derive(Readable)]` generate
+#[doc(hidden)]
+pub mod __private {
+    pub use helper::{CheckedInt, ReadFromStr, ReadReadable, Reader};
+}
 
 // TODO:declare macro version
 #[macro_export]